@@ -0,0 +1,144 @@
+use super::{read_number, read_trimmed, Collector, Registry};
+use crate::system_info::{is_termux, BatteryInfo, InfoValue};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Arc;
+
+const POWER_SUPPLY_CLASS: &str = "/sys/class/power_supply";
+
+pub fn register(registry: &mut Registry) {
+    registry.register(Arc::new(Battery));
+}
+
+/// Every battery's charge, state, health and time remaining. Machines without
+/// a battery, such as desktops, report nothing.
+struct Battery;
+
+impl Collector for Battery {
+    fn key(&self) -> &'static str {
+        "Battery"
+    }
+
+    fn collect(&self) -> Option<InfoValue> {
+        if is_termux() {
+            return termux_battery();
+        }
+
+        let supplies = power_supplies();
+        let ac_online = supplies
+            .iter()
+            .filter(|supply| {
+                read_trimmed(&supply.join("type"))
+                    .is_some_and(|kind| kind == "Mains" || kind == "USB")
+            })
+            .any(|supply| read_number(&supply.join("online")) == Some(1));
+        let batteries: Vec<&PathBuf> = supplies
+            .iter()
+            .filter(|supply| read_trimmed(&supply.join("type")).as_deref() == Some("Battery"))
+            // Mice, keyboards and headsets report their batteries with a Device scope
+            .filter(|supply| read_trimmed(&supply.join("scope")).as_deref() != Some("Device"))
+            .collect();
+
+        let batteries: Vec<BatteryInfo> = batteries
+            .iter()
+            .filter_map(|battery| describe(battery, ac_online))
+            .collect();
+        (!batteries.is_empty()).then_some(InfoValue::Batteries(batteries))
+    }
+}
+
+fn power_supplies() -> Vec<PathBuf> {
+    let mut supplies: Vec<PathBuf> = fs::read_dir(POWER_SUPPLY_CLASS)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .collect()
+        })
+        .unwrap_or_default();
+    supplies.sort();
+    supplies
+}
+
+fn describe(battery: &Path, ac_online: bool) -> Option<BatteryInfo> {
+    // Batteries report either energy (µWh, µW) or charge (µAh, µA)
+    let (now, full, design, rate) = match read_number(&battery.join("energy_now")) {
+        Some(energy) => (
+            Some(energy),
+            read_number(&battery.join("energy_full")),
+            read_number(&battery.join("energy_full_design")),
+            read_number(&battery.join("power_now")),
+        ),
+        None => (
+            read_number(&battery.join("charge_now")),
+            read_number(&battery.join("charge_full")),
+            read_number(&battery.join("charge_full_design")),
+            read_number(&battery.join("current_now")),
+        ),
+    };
+
+    let capacity = read_number(&battery.join("capacity")).or_else(|| {
+        let (now, full) = (now?, full.filter(|full| *full > 0)?);
+        Some((now * 100 / full).min(100))
+    })?;
+    let status = read_trimmed(&battery.join("status")).unwrap_or_else(|| "Unknown".to_string());
+
+    let rate = rate.filter(|rate| *rate > 0);
+    let time_to_empty = (status == "Discharging")
+        .then(|| {
+            read_number(&battery.join("time_to_empty_now")).or_else(|| Some(now? * 3600 / rate?))
+        })
+        .flatten();
+    let time_to_full = (status == "Charging")
+        .then(|| {
+            read_number(&battery.join("time_to_full_now"))
+                .or_else(|| Some(full?.saturating_sub(now?) * 3600 / rate?))
+        })
+        .flatten();
+
+    Some(BatteryInfo {
+        name: battery.file_name()?.to_string_lossy().into_owned(),
+        capacity_percent: capacity,
+        status,
+        ac_connected: ac_online,
+        time_to_empty,
+        time_to_full,
+        health_percent: full
+            .zip(design.filter(|design| *design > 0))
+            .map(|(full, design)| full * 100 / design),
+        condition: None,
+    })
+}
+
+/// Termux has no access to sysfs, so it asks the Termux:API app.
+fn termux_battery() -> Option<InfoValue> {
+    let output = Command::new("termux-battery-status").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let status: serde_json::Value = serde_json::from_slice(&output.stdout).ok()?;
+    let text = |key: &str| status.get(key).and_then(|value| value.as_str());
+
+    Some(InfoValue::Batteries(vec![BatteryInfo {
+        name: "battery".to_string(),
+        capacity_percent: status.get("percentage")?.as_u64()?,
+        status: text("status")
+            .map(capitalize)
+            .unwrap_or_else(|| "Unknown".to_string()),
+        ac_connected: text("plugged").is_some_and(|plugged| plugged != "UNPLUGGED"),
+        time_to_empty: None,
+        time_to_full: None,
+        health_percent: None,
+        condition: text("health").map(capitalize),
+    }]))
+}
+
+/// "NOT_CHARGING" becomes "Not charging".
+fn capitalize(value: &str) -> String {
+    let value = value.replace('_', " ").to_lowercase();
+    let mut chars = value.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => value,
+    }
+}
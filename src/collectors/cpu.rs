@@ -0,0 +1,317 @@
+use super::{module_str, read_number, Collector, Registry};
+use crate::system_info::{is_android, is_termux, InfoValue};
+use regex::Regex;
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use toml::Value;
+
+/// Used when `[modules.CPU]` has no `format`.
+const DEFAULT_FORMAT: &str = "{model} ({cores}C/{threads}T) @ {max_ghz}GHz";
+
+const CPU_SYSFS: &str = "/sys/devices/system/cpu";
+
+pub fn register(registry: &mut Registry, config: &Value) {
+    let format = module_str(config, "CPU", "format").unwrap_or_else(|| DEFAULT_FORMAT.to_string());
+    registry.register(Arc::new(Cpu { format }));
+    registry.register(Arc::new(CpuModel));
+}
+
+/// Summarizes the processor through `format`, whose placeholders are
+/// `{model}`, `{cores}`, `{threads}`, `{max_ghz}`, `{max_mhz}`, `{cur_ghz}`,
+/// `{cur_mhz}` and `{arch}`.
+struct Cpu {
+    format: String,
+}
+
+impl Collector for Cpu {
+    fn key(&self) -> &'static str {
+        "CPU"
+    }
+
+    fn collect(&self) -> Option<InfoValue> {
+        let cpuinfo = fs::read_to_string("/proc/cpuinfo").unwrap_or_default();
+        let model = cpu_model(&cpuinfo).map(|model| short_model(&model));
+        let text = self.render(
+            model.as_deref(),
+            core_counts(&cpuinfo),
+            frequencies(&cpuinfo),
+        );
+        Some(InfoValue::Text(text))
+    }
+}
+
+impl Cpu {
+    /// Fills in `format`, given the core and thread counts and the maximum and
+    /// current clocks in MHz.
+    fn render(
+        &self,
+        model: Option<&str>,
+        (cores, threads): (usize, usize),
+        (max_mhz, cur_mhz): (Option<f64>, Option<f64>),
+    ) -> String {
+        let mut text = self.format.clone();
+        // An unknown frequency takes its "@" and unit along with it
+        for (name, mhz) in [("max", max_mhz), ("cur", cur_mhz)] {
+            let Some(mhz) = mhz else {
+                let unknown = Regex::new(&format!(r"\s*@?\s*\{{{}_[gm]hz\}}\s*([GM]Hz)?", name))
+                    .expect("valid regex");
+                text = unknown.replace_all(&text, "").into_owned();
+                continue;
+            };
+            text = text
+                .replace(
+                    &format!("{{{}_ghz}}", name),
+                    &format!("{:.2}", mhz / 1000.0),
+                )
+                .replace(&format!("{{{}_mhz}}", name), &format!("{:.0}", mhz));
+        }
+        let text = text
+            .replace("{model}", model.unwrap_or("Unknown CPU"))
+            .replace("{cores}", &cores.to_string())
+            .replace("{threads}", &threads.to_string())
+            .replace("{arch}", std::env::consts::ARCH);
+        text.trim().to_string()
+    }
+}
+
+struct CpuModel;
+
+impl Collector for CpuModel {
+    fn key(&self) -> &'static str {
+        "CPU Model"
+    }
+
+    fn collect(&self) -> Option<InfoValue> {
+        let model = fs::read_to_string("/proc/cpuinfo")
+            .ok()
+            .and_then(|content| cpu_model(&content))
+            .unwrap_or_else(|| "Unknown CPU Model".to_string());
+        Some(InfoValue::Text(model))
+    }
+}
+
+/// The value of the first `field : value` line in /proc/cpuinfo.
+fn cpuinfo_field<'a>(cpuinfo: &'a str, field: &str) -> Option<&'a str> {
+    cpuinfo.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        (key.trim() == field)
+            .then(|| value.trim())
+            .filter(|value| !value.is_empty())
+    })
+}
+
+/// The processor name. x86 reports `model name`; ARM kernels, Android in
+/// particular, often only report `Hardware` or `Processor`.
+fn cpu_model(cpuinfo: &str) -> Option<String> {
+    let fields: &[&str] = if is_termux() || is_android() {
+        &["Hardware", "model name", "Processor"]
+    } else {
+        &["model name", "Hardware", "Processor", "cpu model", "cpu"]
+    };
+    fields
+        .iter()
+        .find_map(|field| cpuinfo_field(cpuinfo, field))
+        .map(String::from)
+}
+
+/// Drops trademark marks and the base clock some vendors put in the name,
+/// since the frequency is reported separately.
+fn short_model(model: &str) -> String {
+    let model = model.split(" @ ").next().unwrap_or(model);
+    model
+        .replace("(R)", "")
+        .replace("(TM)", "")
+        .replace("(tm)", "")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Physical cores and logical processors. Cores are told apart by their
+/// package and core ids, from /proc/cpuinfo or else from sysfs topology.
+fn core_counts(cpuinfo: &str) -> (usize, usize) {
+    let mut threads = 0;
+    let mut cores = HashSet::new();
+    let mut package = None;
+    for line in cpuinfo.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        match key.trim() {
+            "processor" => threads += 1,
+            "physical id" => package = Some(value.trim().to_string()),
+            "core id" => {
+                cores.insert((package.clone(), value.trim().to_string()));
+            }
+            _ => {}
+        }
+    }
+
+    if threads == 0 {
+        threads = std::thread::available_parallelism()
+            .map(usize::from)
+            .unwrap_or(1);
+    }
+    if cores.is_empty() {
+        cores = sysfs_cpus()
+            .iter()
+            .filter_map(|cpu| {
+                let topology = cpu.join("topology");
+                let package = fs::read_to_string(topology.join("physical_package_id")).ok();
+                let core = fs::read_to_string(topology.join("core_id")).ok()?;
+                Some((
+                    package.map(|p| p.trim().to_string()),
+                    core.trim().to_string(),
+                ))
+            })
+            .collect();
+    }
+
+    let cores = if cores.is_empty() {
+        threads
+    } else {
+        cores.len()
+    };
+    (cores, threads)
+}
+
+/// The highest rated and the highest current clock across all CPUs, in MHz.
+/// Without cpufreq (as in most VMs) the current clock comes from
+/// /proc/cpuinfo, and the rated one from the current.
+fn frequencies(cpuinfo: &str) -> (Option<f64>, Option<f64>) {
+    let highest_khz = |file: &str| {
+        sysfs_cpus()
+            .iter()
+            .filter_map(|cpu| read_number(&cpu.join("cpufreq").join(file)))
+            .map(|khz| khz as f64)
+            .reduce(f64::max)
+            .map(|khz| khz / 1000.0)
+    };
+
+    let current = highest_khz("scaling_cur_freq").or_else(|| {
+        cpuinfo
+            .lines()
+            .filter_map(|line| {
+                let (key, value) = line.split_once(':')?;
+                (key.trim() == "cpu MHz").then(|| value.trim().parse::<f64>().ok())?
+            })
+            .reduce(f64::max)
+    });
+    let max = highest_khz("cpuinfo_max_freq").or(current);
+    (max, current)
+}
+
+/// The `cpuN` directories under /sys/devices/system/cpu.
+fn sysfs_cpus() -> Vec<PathBuf> {
+    fs::read_dir(CPU_SYSFS)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| {
+                    path.file_name()
+                        .and_then(|name| name.to_str())
+                        .and_then(|name| name.strip_prefix("cpu"))
+                        .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(format: &str, frequencies: (Option<f64>, Option<f64>)) -> String {
+        let cpu = Cpu {
+            format: format.to_string(),
+        };
+        cpu.render(Some("Ryzen 7 5800X"), (8, 16), frequencies)
+    }
+
+    #[test]
+    fn default_format() {
+        assert_eq!(
+            render(DEFAULT_FORMAT, (Some(4850.0), Some(3600.0))),
+            "Ryzen 7 5800X (8C/16T) @ 4.85GHz"
+        );
+    }
+
+    #[test]
+    fn unknown_frequency_takes_its_at_and_unit_along() {
+        assert_eq!(
+            render(DEFAULT_FORMAT, (None, None)),
+            "Ryzen 7 5800X (8C/16T)"
+        );
+        assert_eq!(
+            render(
+                "{model} @ {cur_mhz} MHz, {cores} cores",
+                (Some(4850.0), None)
+            ),
+            "Ryzen 7 5800X, 8 cores"
+        );
+        assert_eq!(
+            render("{max_mhz}MHz/{cur_ghz}GHz", (Some(4850.0), None)),
+            "4850MHz/"
+        );
+    }
+
+    #[test]
+    fn unknown_model() {
+        let cpu = Cpu {
+            format: "{model} {threads}T".to_string(),
+        };
+        assert_eq!(cpu.render(None, (1, 2), (None, None)), "Unknown CPU 2T");
+    }
+
+    #[test]
+    fn short_model_drops_marks_and_base_clock() {
+        assert_eq!(
+            short_model("Intel(R) Core(TM) i5-8350U CPU @ 1.70GHz"),
+            "Intel Core i5-8350U CPU"
+        );
+        assert_eq!(
+            short_model("AMD Ryzen 7 5800X 8-Core Processor"),
+            "AMD Ryzen 7 5800X 8-Core Processor"
+        );
+        assert_eq!(short_model("AMD Athlon(tm)  II X2"), "AMD Athlon II X2");
+    }
+
+    #[test]
+    fn core_counts_tell_hyperthreads_apart() {
+        let cpuinfo = "\
+processor\t: 0
+physical id\t: 0
+core id\t\t: 0
+
+processor\t: 1
+physical id\t: 0
+core id\t\t: 1
+
+processor\t: 2
+physical id\t: 0
+core id\t\t: 0
+
+processor\t: 3
+physical id\t: 0
+core id\t\t: 1
+";
+        assert_eq!(core_counts(cpuinfo), (2, 4));
+    }
+
+    #[test]
+    fn core_counts_keep_packages_apart() {
+        let cpuinfo = "\
+processor\t: 0
+physical id\t: 0
+core id\t\t: 0
+
+processor\t: 1
+physical id\t: 1
+core id\t\t: 0
+";
+        assert_eq!(core_counts(cpuinfo), (2, 2));
+    }
+}
@@ -0,0 +1,296 @@
+use super::{Collector, Registry};
+use crate::system_info::{is_android, is_termux, InfoValue};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Arc;
+
+/// Window managers and compositors by process name, with display names.
+const WINDOW_MANAGERS: &[(&str, &str)] = &[
+    ("sway", "Sway"),
+    ("Hyprland", "Hyprland"),
+    ("kwin_wayland", "KWin"),
+    ("kwin_x11", "KWin"),
+    ("kwin", "KWin"),
+    ("gnome-shell", "Mutter"),
+    ("mutter", "Mutter"),
+    ("i3", "i3"),
+    ("bspwm", "bspwm"),
+    ("openbox", "Openbox"),
+    ("xfwm4", "Xfwm4"),
+    ("marco", "Marco"),
+    ("muffin", "Muffin"),
+    ("cinnamon", "Muffin"),
+    ("awesome", "awesome"),
+    ("dwm", "dwm"),
+    ("herbstluftwm", "herbstluftwm"),
+    ("qtile", "Qtile"),
+    ("xmonad", "xmonad"),
+    ("river", "river"),
+    ("wayfire", "Wayfire"),
+    ("labwc", "labwc"),
+    ("niri", "niri"),
+    ("fluxbox", "Fluxbox"),
+    ("icewm", "IceWM"),
+    ("enlightenment", "Enlightenment"),
+    ("weston", "Weston"),
+    ("compiz", "Compiz"),
+    ("spectrwm", "spectrwm"),
+    ("leftwm", "LeftWM"),
+    ("dwl", "dwl"),
+];
+
+/// Desktops whose settings live in the org.gnome.desktop.interface schema.
+const GSETTINGS_DESKTOPS: &[&str] = &["GNOME", "Unity", "Budgie", "Pantheon", "Cinnamon", "MATE"];
+
+pub fn register(registry: &mut Registry) {
+    registry.register(Arc::new(WindowManager));
+    registry.register(Arc::new(Theme));
+    registry.register(Arc::new(Icons));
+    registry.register(Arc::new(Font));
+    registry.register(Arc::new(Cursor));
+}
+
+/// The first known window manager among the running processes.
+struct WindowManager;
+
+impl Collector for WindowManager {
+    fn key(&self) -> &'static str {
+        "WM"
+    }
+
+    fn collect(&self) -> Option<InfoValue> {
+        if is_termux() || is_android() {
+            return None;
+        }
+        let running: Vec<String> = fs::read_dir("/proc")
+            .ok()?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().parse::<u32>().is_ok())
+            .filter_map(|entry| fs::read_to_string(entry.path().join("comm")).ok())
+            .map(|comm| comm.trim().to_string())
+            .collect();
+        WINDOW_MANAGERS
+            .iter()
+            .find(|(process, _)| running.iter().any(|name| name == process))
+            .map(|(_, name)| InfoValue::Text(name.to_string()))
+    }
+}
+
+/// The widget theme of every toolkit that has one configured.
+struct Theme;
+
+impl Collector for Theme {
+    fn key(&self) -> &'static str {
+        "Theme"
+    }
+
+    fn collect(&self) -> Option<InfoValue> {
+        let qt = kde_setting("kdeglobals", "KDE", "widgetStyle");
+        let gtk = gtk_setting("gtk-theme", "gtk-theme-name");
+        toolkit_list([qt.map(|theme| (theme, "Qt")), gtk])
+    }
+}
+
+struct Icons;
+
+impl Collector for Icons {
+    fn key(&self) -> &'static str {
+        "Icons"
+    }
+
+    fn collect(&self) -> Option<InfoValue> {
+        let qt = kde_setting("kdeglobals", "Icons", "Theme");
+        let gtk = gtk_setting("icon-theme", "gtk-icon-theme-name");
+        toolkit_list([qt.map(|icons| (icons, "Qt")), gtk])
+    }
+}
+
+struct Font;
+
+impl Collector for Font {
+    fn key(&self) -> &'static str {
+        "Font"
+    }
+
+    fn collect(&self) -> Option<InfoValue> {
+        // KDE stores fonts as "Noto Sans,10,-1,5,50,0,0,0,0,0"
+        let qt = kde_setting("kdeglobals", "General", "font").map(|font| {
+            let mut parts = font.split(',');
+            let family = parts.next().unwrap_or_default();
+            match parts.next() {
+                Some(size) => format!("{} {}", family, size),
+                None => family.to_string(),
+            }
+        });
+        let gtk = gtk_setting("font-name", "gtk-font-name");
+        toolkit_list([qt.map(|font| (font, "Qt")), gtk])
+    }
+}
+
+/// The cursor theme and size, from KDE, GTK, Xresources or the default
+/// icon theme, in that order.
+struct Cursor;
+
+impl Collector for Cursor {
+    fn key(&self) -> &'static str {
+        "Cursor"
+    }
+
+    fn collect(&self) -> Option<InfoValue> {
+        if is_termux() || is_android() {
+            return None;
+        }
+        let xresources = home_file(".Xresources")
+            .and_then(|path| fs::read_to_string(path).ok())
+            .unwrap_or_default();
+
+        let (theme, size) = if let Some(theme) = kde_setting("kcminputrc", "Mouse", "cursorTheme") {
+            (theme, kde_setting("kcminputrc", "Mouse", "cursorSize"))
+        } else if let Some((theme, _)) = gtk_setting("cursor-theme", "gtk-cursor-theme-name") {
+            let size = gtk_setting("cursor-size", "gtk-cursor-theme-size").map(|(size, _)| size);
+            (theme, size)
+        } else if let Some(theme) = xresource(&xresources, "Xcursor.theme") {
+            (theme, xresource(&xresources, "Xcursor.size"))
+        } else {
+            let index = home_file(".icons/default/index.theme")?;
+            (ini_value(&index, "Icon Theme", "Inherits")?, None)
+        };
+
+        Some(InfoValue::Text(match size {
+            Some(size) => format!("{} ({}px)", theme, size),
+            None => theme,
+        }))
+    }
+}
+
+/// Formats settings as "Breeze [Qt], Adwaita [GTK3]".
+fn toolkit_list<const N: usize>(settings: [Option<(String, &str)>; N]) -> Option<InfoValue> {
+    if is_termux() || is_android() {
+        return None;
+    }
+    let values: Vec<String> = settings
+        .into_iter()
+        .flatten()
+        .map(|(value, toolkit)| format!("{} [{}]", value, toolkit))
+        .collect();
+    (!values.is_empty()).then(|| InfoValue::Text(values.join(", ")))
+}
+
+/// A GTK setting and the toolkit version it was found for. GNOME-based
+/// desktops are asked through gsettings; otherwise the settings.ini files,
+/// system dconf keyfiles and gtkrc-2.0 are read.
+fn gtk_setting(gsettings_key: &str, ini_key: &str) -> Option<(String, &'static str)> {
+    let desktop = std::env::var("XDG_CURRENT_DESKTOP").unwrap_or_default();
+    if desktop
+        .split(':')
+        .any(|name| GSETTINGS_DESKTOPS.contains(&name))
+    {
+        if let Some(value) = gsettings(gsettings_key) {
+            return Some((value, "GTK3"));
+        }
+    }
+
+    let config = dirs::config_dir()?;
+    for (dir, toolkit) in [("gtk-3.0", "GTK3"), ("gtk-4.0", "GTK4")] {
+        if let Some(value) = ini_value(&config.join(dir).join("settings.ini"), "Settings", ini_key)
+        {
+            return Some((value, toolkit));
+        }
+    }
+    if let Some(value) = dconf_keyfile(gsettings_key) {
+        return Some((value, "GTK3"));
+    }
+    let gtkrc = home_file(".gtkrc-2.0")?;
+    let value = fs::read_to_string(gtkrc).ok()?.lines().find_map(|line| {
+        let (key, value) = line.split_once('=')?;
+        (key.trim() == ini_key).then(|| unquote(value))
+    })?;
+    Some((value, "GTK2"))
+}
+
+fn gsettings(key: &str) -> Option<String> {
+    let output = Command::new("gsettings")
+        .args(["get", "org.gnome.desktop.interface", key])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let value = unquote(&String::from_utf8_lossy(&output.stdout));
+    // gsettings prints integers as "uint32 24"
+    let value = value.trim_start_matches("uint32 ").to_string();
+    (!value.is_empty()).then_some(value)
+}
+
+/// A key of the GNOME interface schema in the system dconf keyfiles, such as
+/// /etc/dconf/db/local.d/00-theme.
+fn dconf_keyfile(key: &str) -> Option<String> {
+    let mut keyfiles: Vec<PathBuf> = fs::read_dir("/etc/dconf/db")
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "d"))
+        .filter_map(|dir| fs::read_dir(dir).ok())
+        .flatten()
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file())
+        .collect();
+    // Later files take precedence in dconf, so search them first
+    keyfiles.sort();
+    keyfiles
+        .iter()
+        .rev()
+        .find_map(|path| ini_value(path, "org/gnome/desktop/interface", key))
+}
+
+/// A value from a KDE config file in $XDG_CONFIG_HOME.
+fn kde_setting(file: &str, section: &str, key: &str) -> Option<String> {
+    ini_value(&dirs::config_dir()?.join(file), section, key)
+}
+
+/// An X resource such as `Xcursor.theme: Adwaita`.
+fn xresource(resources: &str, name: &str) -> Option<String> {
+    resources.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        (key.trim() == name)
+            .then(|| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    })
+}
+
+/// The value of `key` in `[section]` of an ini-style file.
+fn ini_value(path: &Path, section: &str, key: &str) -> Option<String> {
+    let contents = fs::read_to_string(path).ok()?;
+    let mut in_section = false;
+    for line in contents.lines().map(str::trim) {
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            in_section = name == section;
+            continue;
+        }
+        if !in_section {
+            continue;
+        }
+        if let Some((k, value)) = line.split_once('=') {
+            if k.trim() == key {
+                let value = unquote(value);
+                return (!value.is_empty()).then_some(value);
+            }
+        }
+    }
+    None
+}
+
+/// Strips whitespace and one pair of single or double quotes.
+fn unquote(value: &str) -> String {
+    let value = value.trim();
+    value
+        .strip_prefix('\'')
+        .and_then(|v| v.strip_suffix('\''))
+        .or_else(|| value.strip_prefix('"').and_then(|v| v.strip_suffix('"')))
+        .unwrap_or(value)
+        .to_string()
+}
+
+fn home_file(path: &str) -> Option<PathBuf> {
+    Some(dirs::home_dir()?.join(path))
+}
@@ -0,0 +1,98 @@
+use super::{Collector, Registry};
+use crate::system_info::{is_android, is_termux, DiskInfo, InfoValue};
+use std::sync::Arc;
+use sysinfo::{DiskExt, System, SystemExt};
+
+pub fn register(registry: &mut Registry) {
+    registry.register(Arc::new(Disk));
+}
+
+struct Disk;
+
+impl Collector for Disk {
+    fn key(&self) -> &'static str {
+        "Disk"
+    }
+
+    fn collect(&self) -> Option<InfoValue> {
+        Some(InfoValue::Disks(physical_disks()))
+    }
+}
+
+fn physical_disks() -> Vec<DiskInfo> {
+    let mut system = System::new();
+    system.refresh_disks_list();
+    system.refresh_disks();
+
+    let mut physical_disks = Vec::new();
+
+    for disk in system.disks() {
+        let mount_point = disk.mount_point().to_string_lossy();
+        let file_system = String::from_utf8_lossy(disk.file_system());
+
+        // Filter out Android-specific partitions and subvolumes
+        if is_android() || is_termux() {
+            // Skip Android system partitions, subvolumes, and temporary mounts
+            if mount_point.contains("/system")
+                || mount_point.contains("/vendor")
+                || mount_point.contains("/product")
+                || mount_point.contains("/data")
+                || mount_point.contains("/cache")
+                || mount_point.contains("/mnt")
+                || mount_point.contains("/storage")
+                || mount_point.contains("/dev/block")
+                || mount_point.contains("tmpfs")
+                || mount_point.contains("proc")
+                || mount_point.contains("sysfs")
+                || mount_point.contains("devpts")
+                || mount_point.contains("debugfs")
+                || mount_point.contains("configfs")
+                || mount_point.contains("selinuxfs")
+                || mount_point.contains("cgroup")
+                || mount_point.contains("pstore")
+                || mount_point.contains("efivarfs")
+                || mount_point.contains("fuse")
+                || mount_point.contains("sdcardfs")
+                || mount_point.contains("f2fs")
+                || mount_point.contains("ext4") && mount_point.contains("/dev/block")
+            {
+                continue;
+            }
+        } else {
+            // On regular Linux, filter out subvolumes and temporary filesystems
+            if mount_point.contains("@")
+                || mount_point.contains("tmpfs")
+                || mount_point.contains("proc")
+                || mount_point.contains("sysfs")
+                || mount_point.contains("devpts")
+                || mount_point.contains("debugfs")
+                || mount_point.contains("configfs")
+                || mount_point.contains("selinuxfs")
+                || mount_point.contains("cgroup")
+                || mount_point.contains("pstore")
+                || mount_point.contains("efivarfs")
+                || mount_point.contains("fuse")
+                || mount_point.contains("squashfs")
+                || mount_point.contains("overlay")
+            {
+                continue;
+            }
+        }
+
+        // Only show actual disk partitions with meaningful space
+        let total_bytes = disk.total_space();
+        if total_bytes > 100 * 1024 * 1024 {
+            // Only show partitions with more than 100MB
+            physical_disks.push(DiskInfo {
+                mount_point: mount_point.to_string(),
+                file_system: file_system.to_string(),
+                total_bytes,
+                available_bytes: disk.available_space(),
+            });
+        }
+    }
+
+    // Sort by mount point for consistent output
+    physical_disks.sort_by(|a, b| a.mount_point.cmp(&b.mount_point));
+    physical_disks
+}
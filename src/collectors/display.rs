@@ -0,0 +1,217 @@
+use super::{command_output, read_trimmed, wayland, Collector, Registry, DRM_CLASS};
+use crate::system_info::{is_android, is_termux, DisplayInfo, InfoValue};
+use std::fs;
+use std::sync::Arc;
+
+pub fn register(registry: &mut Registry) {
+    registry.register(Arc::new(Display));
+}
+
+/// Every connected monitor's current mode. Wayland compositors are asked over
+/// their socket, X servers through `xrandr`. Without either, as on a bare
+/// console, the preferred mode is read from DRM. Headless machines report
+/// nothing.
+struct Display;
+
+impl Collector for Display {
+    fn key(&self) -> &'static str {
+        "Display"
+    }
+
+    fn collect(&self) -> Option<InfoValue> {
+        if is_termux() || is_android() {
+            return None;
+        }
+
+        let displays = if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+            // $DISPLAY is XWayland here, whose modes are emulated, so its
+            // xrandr output is not trusted
+            wayland::outputs().filter(|outputs| !outputs.is_empty())
+        } else if std::env::var_os("DISPLAY").is_some() {
+            command_output("xrandr", &["--current"])
+                .map(|output| parse_xrandr(&output))
+                .filter(|outputs| !outputs.is_empty())
+        } else {
+            None
+        };
+        let displays = displays.unwrap_or_else(drm_displays);
+
+        (!displays.is_empty()).then_some(InfoValue::Displays(displays))
+    }
+}
+
+/// Parses `xrandr --current`, where the mode in use is marked with `*`:
+///
+/// ```text
+/// DP-1 connected primary 2560x1440+0+0 (normal left inverted right x axis y axis) 597mm x 336mm
+///    2560x1440    143.97*+  59.95
+/// ```
+fn parse_xrandr(output: &str) -> Vec<DisplayInfo> {
+    let mut displays = Vec::new();
+    let mut output_name: Option<String> = None;
+
+    for line in output.lines() {
+        if !line.starts_with(' ') {
+            let mut words = line.split_whitespace();
+            let name = words.next().unwrap_or_default();
+            output_name = (words.next() == Some("connected")).then(|| name.to_string());
+            continue;
+        }
+        let Some(name) = &output_name else {
+            continue;
+        };
+        let mut words = line.split_whitespace();
+        let Some(resolution) = words.next().and_then(parse_resolution) else {
+            continue;
+        };
+        if let Some(rate) = words.find(|rate| rate.contains('*')) {
+            displays.push(DisplayInfo {
+                name: name.clone(),
+                width: resolution.0,
+                height: resolution.1,
+                refresh_hz: rate.trim_end_matches(['*', '+']).parse().ok(),
+                scale: None,
+            });
+            output_name = None;
+        }
+    }
+    displays
+}
+
+/// Connected DRM connectors (`card0-DP-1` and so on) with their preferred
+/// mode. The refresh rate comes from the first detailed timing in the EDID.
+fn drm_displays() -> Vec<DisplayInfo> {
+    let mut connectors: Vec<_> = fs::read_dir(DRM_CLASS)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| {
+                    path.file_name()
+                        .and_then(|name| name.to_str())
+                        .is_some_and(|name| name.starts_with("card") && name.contains('-'))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    connectors.sort();
+
+    connectors
+        .iter()
+        .filter(|connector| read_trimmed(&connector.join("status")).as_deref() == Some("connected"))
+        .filter_map(|connector| {
+            let modes = read_trimmed(&connector.join("modes"))?;
+            let (width, height) = parse_resolution(modes.lines().next()?)?;
+            let name = connector.file_name()?.to_str()?;
+            // "card0-DP-1" is known to users as "DP-1"
+            let name = name.split_once('-').map_or(name, |(_, output)| output);
+            Some(DisplayInfo {
+                name: name.to_string(),
+                width,
+                height,
+                refresh_hz: fs::read(connector.join("edid"))
+                    .ok()
+                    .and_then(|edid| edid_refresh(&edid)),
+                scale: None,
+            })
+        })
+        .collect()
+}
+
+/// The refresh rate of the EDID's first detailed timing descriptor, which is
+/// the monitor's preferred mode.
+fn edid_refresh(edid: &[u8]) -> Option<f64> {
+    let timing = edid.get(54..72)?;
+    let pixel_clock = u16::from_le_bytes([timing[0], timing[1]]) as f64 * 10_000.0;
+    if pixel_clock == 0.0 {
+        return None;
+    }
+    let h_total = timing[2] as u32
+        + ((timing[4] as u32 & 0xF0) << 4)
+        + timing[3] as u32
+        + ((timing[4] as u32 & 0x0F) << 8);
+    let v_total = timing[5] as u32
+        + ((timing[7] as u32 & 0xF0) << 4)
+        + timing[6] as u32
+        + ((timing[7] as u32 & 0x0F) << 8);
+    if h_total == 0 || v_total == 0 {
+        return None;
+    }
+    Some(pixel_clock / (h_total * v_total) as f64)
+}
+
+/// Parses "2560x1440", ignoring a trailing "i" for interlaced modes.
+fn parse_resolution(resolution: &str) -> Option<(u32, u32)> {
+    let (width, height) = resolution.trim().split_once('x')?;
+    Some((
+        width.parse().ok()?,
+        height.trim_end_matches('i').parse().ok()?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn xrandr_reports_the_current_mode_of_connected_outputs() {
+        let output = "\
+Screen 0: minimum 320 x 200, current 4480 x 1440, maximum 16384 x 16384
+DP-1 connected primary 2560x1440+0+0 (normal left inverted right x axis y axis) 597mm x 336mm
+   2560x1440    143.97*+  59.95
+   1920x1080     60.00
+HDMI-1 disconnected (normal left inverted right x axis y axis)
+   1920x1080     60.00
+eDP-1 connected 1920x1080+2560+0 (normal left inverted right x axis y axis) 309mm x 174mm
+   1920x1080     60.01 +  59.93*
+   1280x720i     60.00
+";
+        assert_eq!(
+            parse_xrandr(output),
+            vec![
+                DisplayInfo {
+                    name: "DP-1".to_string(),
+                    width: 2560,
+                    height: 1440,
+                    refresh_hz: Some(143.97),
+                    scale: None,
+                },
+                DisplayInfo {
+                    name: "eDP-1".to_string(),
+                    width: 1920,
+                    height: 1080,
+                    refresh_hz: Some(59.93),
+                    scale: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn xrandr_skips_connected_outputs_that_are_off() {
+        let output =
+            "DP-2 connected (normal left inverted right x axis y axis)\n   1920x1080     60.00 +\n";
+        assert!(parse_xrandr(output).is_empty());
+    }
+
+    #[test]
+    fn edid_refresh_comes_from_the_first_detailed_timing() {
+        // 1920x1080 at 148.5 MHz with 280 and 45 blanking lines, the CEA 1080p60 timing
+        let mut edid = vec![0u8; 128];
+        edid[54..62].copy_from_slice(&[0x02, 0x3a, 0x80, 0x18, 0x71, 0x38, 0x2d, 0x40]);
+        let refresh = edid_refresh(&edid).unwrap();
+        assert!((refresh - 60.0).abs() < 0.001, "{}", refresh);
+    }
+
+    #[test]
+    fn edid_refresh_needs_a_timing() {
+        assert_eq!(edid_refresh(&[0u8; 128]), None);
+        assert_eq!(edid_refresh(&[0u8; 60]), None);
+    }
+
+    #[test]
+    fn resolutions_may_be_interlaced() {
+        assert_eq!(parse_resolution("1920x1080"), Some((1920, 1080)));
+        assert_eq!(parse_resolution("1920x1080i"), Some((1920, 1080)));
+        assert_eq!(parse_resolution("preferred"), None);
+    }
+}
@@ -0,0 +1,385 @@
+use super::{Collector, Registry, DRM_CLASS};
+use crate::system_info::{is_android, is_termux, GpuInfo, GpuKind, InfoValue};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Arc;
+
+const PCI_DEVICES: &str = "/sys/bus/pci/devices";
+
+/// Where distributions install the PCI ID database (hwdata, pciutils).
+const PCI_IDS_PATHS: &[&str] = &[
+    "/usr/share/hwdata/pci.ids",
+    "/usr/share/misc/pci.ids",
+    "/usr/share/pci.ids",
+    "/usr/local/share/hwdata/pci.ids",
+    "/usr/local/share/pci.ids",
+];
+
+const VENDOR_INTEL: u16 = 0x8086;
+const VENDOR_NVIDIA: u16 = 0x10de;
+const VENDOR_AMD: u16 = 0x1002;
+
+/// AMD APUs carve out at most a few hundred MiB of system memory as VRAM.
+const APU_VRAM_LIMIT: u64 = 1 << 30;
+
+pub fn register(registry: &mut Registry) {
+    registry.register(Arc::new(GpuModel));
+}
+
+/// Lists every GPU, one per line, marked as integrated or discrete when that
+/// can be told.
+struct GpuModel;
+
+impl Collector for GpuModel {
+    fn key(&self) -> &'static str {
+        "GPU Model"
+    }
+
+    fn collect(&self) -> Option<InfoValue> {
+        let gpus = if is_termux() || is_android() {
+            vec![GpuInfo {
+                name: android_gpu(),
+                kind: None,
+            }]
+        } else {
+            let ids = read_pci_ids().unwrap_or_default();
+            gpu_devices()
+                .iter()
+                .map(|gpu| describe(gpu, &ids))
+                .collect()
+        };
+        Some(InfoValue::Gpus(gpus))
+    }
+}
+
+/// A graphics device found in sysfs.
+enum GpuDevice {
+    Pci {
+        path: PathBuf,
+        vendor: u16,
+        device: u16,
+    },
+    /// A GPU built into a SoC, known only by its DRM driver.
+    Platform { driver: String },
+}
+
+/// Display controllers on the PCI bus, which covers "VGA compatible",
+/// "3D" (e.g. NVIDIA Optimus) and "Display" controllers, plus DRM cards that
+/// are not PCI devices.
+fn gpu_devices() -> Vec<GpuDevice> {
+    let mut pci_paths: Vec<PathBuf> = fs::read_dir(PCI_DEVICES)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| {
+                    read_hex(&path.join("class")).is_some_and(|class| class >> 16 == 0x03)
+                })
+                .filter_map(|path| fs::canonicalize(path).ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    pci_paths.sort();
+
+    let mut devices: Vec<GpuDevice> = pci_paths
+        .into_iter()
+        .filter_map(|path| {
+            let vendor = read_hex(&path.join("vendor"))? as u16;
+            let device = read_hex(&path.join("device"))? as u16;
+            Some(GpuDevice::Pci {
+                path,
+                vendor,
+                device,
+            })
+        })
+        .collect();
+
+    let mut drivers: Vec<String> = drm_cards()
+        .into_iter()
+        .filter_map(|card| fs::canonicalize(card.join("device")).ok())
+        .filter(|device| !device.join("vendor").exists())
+        .filter_map(|device| {
+            let uevent = fs::read_to_string(device.join("uevent")).ok()?;
+            uevent
+                .lines()
+                .find_map(|line| line.strip_prefix("DRIVER="))
+                .map(String::from)
+        })
+        .collect();
+    drivers.dedup();
+    devices.extend(
+        drivers
+            .into_iter()
+            .map(|driver| GpuDevice::Platform { driver }),
+    );
+    devices
+}
+
+/// The `cardN` entries of /sys/class/drm, skipping connectors like `card0-HDMI-A-1`.
+fn drm_cards() -> Vec<PathBuf> {
+    let mut cards: Vec<PathBuf> = fs::read_dir(DRM_CLASS)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| {
+                    path.file_name()
+                        .and_then(|name| name.to_str())
+                        .and_then(|name| name.strip_prefix("card"))
+                        .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    cards.sort();
+    cards
+}
+
+/// `ids` is the contents of pci.ids, empty when it is not installed.
+fn describe(gpu: &GpuDevice, ids: &str) -> GpuInfo {
+    let (name, kind) = match gpu {
+        GpuDevice::Pci {
+            path,
+            vendor,
+            device,
+        } => (pci_name(ids, *vendor, *device), pci_kind(path, *vendor)),
+        GpuDevice::Platform { driver } => (driver.clone(), Some(GpuKind::Integrated)),
+    };
+    GpuInfo { name, kind }
+}
+
+fn read_pci_ids() -> Option<String> {
+    PCI_IDS_PATHS
+        .iter()
+        .find_map(|path| fs::read_to_string(path).ok())
+}
+
+/// "Vendor Model" from pci.ids, or the raw ids when the device is not listed.
+fn pci_name(ids: &str, vendor: u16, device: u16) -> String {
+    let (vendor_name, device_name) = lookup_pci_ids(ids, vendor, device);
+
+    let vendor_name = vendor_name
+        .map(|name| short_vendor(vendor, &name))
+        .unwrap_or_else(|| short_vendor(vendor, &format!("{:04x}", vendor)));
+    match device_name {
+        // "GA104 [GeForce RTX 3070]" is better known by the part in brackets
+        Some(name) => {
+            let model = name
+                .split_once('[')
+                .and_then(|(_, rest)| rest.split_once(']'))
+                .map(|(model, _)| model.to_string())
+                .unwrap_or(name);
+            // "Meteor Lake-P [Intel Arc Graphics]" already names its vendor
+            if model.split_whitespace().next() == Some(vendor_name.as_str()) {
+                model
+            } else {
+                format!("{} {}", vendor_name, model)
+            }
+        }
+        None => format!("{} [{:04x}:{:04x}]", vendor_name, vendor, device),
+    }
+}
+
+/// Finds the vendor and device names in the pci.ids format: vendors start at
+/// column 0, their devices are indented by one tab, subsystems by two.
+fn lookup_pci_ids(ids: &str, vendor: u16, device: u16) -> (Option<String>, Option<String>) {
+    let vendor_id = format!("{:04x}", vendor);
+    let device_id = format!("{:04x}", device);
+    let mut vendor_name = None;
+
+    for line in ids.lines() {
+        if line.starts_with('#') || line.is_empty() {
+            continue;
+        }
+        if let Some(device_line) = line.strip_prefix('\t') {
+            if vendor_name.is_some() && !device_line.starts_with('\t') {
+                if let Some(name) = device_line.strip_prefix(device_id.as_str()) {
+                    return (vendor_name, Some(name.trim().to_string()));
+                }
+            }
+            continue;
+        }
+        if vendor_name.is_some() {
+            // The next vendor; the device is not listed
+            break;
+        }
+        if let Some(name) = line.strip_prefix(vendor_id.as_str()) {
+            vendor_name = Some(name.trim().to_string());
+        }
+    }
+    (vendor_name, None)
+}
+
+fn short_vendor(vendor: u16, name: &str) -> String {
+    match vendor {
+        VENDOR_INTEL => "Intel".to_string(),
+        VENDOR_NVIDIA => "NVIDIA".to_string(),
+        VENDOR_AMD => "AMD".to_string(),
+        _ => name.to_string(),
+    }
+}
+
+/// NVIDIA GPUs are discrete. Intel and AMD make both: Intel's integrated GPU
+/// is always on the root bus, and AMD's APUs are recognized by their small
+/// VRAM carve-out.
+fn pci_kind(path: &Path, vendor: u16) -> Option<GpuKind> {
+    match vendor {
+        VENDOR_INTEL => intel_kind(path.file_name()?.to_str()?),
+        VENDOR_NVIDIA => Some(GpuKind::Discrete),
+        VENDOR_AMD => match fs::read_to_string(path.join("mem_info_vram_total"))
+            .ok()
+            .and_then(|vram| vram.trim().parse::<u64>().ok())
+        {
+            Some(vram) if vram <= APU_VRAM_LIMIT => Some(GpuKind::Integrated),
+            Some(_) => Some(GpuKind::Discrete),
+            None => None,
+        },
+        _ => None,
+    }
+}
+
+/// Intel's integrated GPU sits at 0000:00:02.0, while its discrete cards,
+/// including Arc, are behind a PCIe bridge on another bus. Both can be named
+/// "Intel Arc Graphics", so the name says nothing.
+fn intel_kind(address: &str) -> Option<GpuKind> {
+    // domain:bus:device.function
+    let bus = address.split(':').nth(1)?;
+    match u8::from_str_radix(bus, 16).ok()? {
+        0 => Some(GpuKind::Integrated),
+        _ => Some(GpuKind::Discrete),
+    }
+}
+
+/// Reads a sysfs attribute such as `0x10de`.
+fn read_hex(path: &Path) -> Option<u32> {
+    let value = fs::read_to_string(path).ok()?;
+    u32::from_str_radix(value.trim().trim_start_matches("0x"), 16).ok()
+}
+
+fn android_gpu() -> String {
+    // On Android, try to get GPU info from different sources
+    if let Ok(output) = Command::new("dumpsys").arg("SurfaceFlinger").output() {
+        if let Ok(content) = String::from_utf8(output.stdout) {
+            if let Some(line) = content.lines().find(|line| line.contains("GLES:")) {
+                return line
+                    .split("GLES:")
+                    .nth(1)
+                    .unwrap_or("Unknown GPU")
+                    .trim()
+                    .to_string();
+            }
+        }
+    }
+
+    // Fallback to reading from /proc
+    if let Ok(content) = fs::read_to_string("/proc/gpuinfo") {
+        if let Some(line) = content.lines().find(|line| line.contains("GPU")) {
+            return line
+                .split(':')
+                .nth(1)
+                .unwrap_or("Unknown GPU")
+                .trim()
+                .to_string();
+        }
+    }
+
+    "ARM Mali GPU".to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PCI_IDS: &str = "\
+# List of PCI ID's
+#
+1002  Advanced Micro Devices, Inc. [AMD/ATI]
+\t73bf  Navi 21 [Radeon RX 6800/6800 XT / 6900 XT]
+\t\t1002 0e3a  Radeon RX 6900 XT
+10de  NVIDIA Corporation
+\t2484  GA104 [GeForce RTX 3070]
+\t\t10de 2484  GeForce RTX 3070
+8086  Intel Corporation
+\t5917  UHD Graphics 620
+\t56a0  DG2 [Arc A770]
+\t7d55  Meteor Lake-P [Intel Arc Graphics]
+\t9a49  TigerLake-LP GT2 [Iris Xe Graphics]
+8087  Intel Corporation
+\t0aaa  Bluetooth 9460/9560 Jefferson Peak (JfP)
+
+# List of known device classes, subclasses and programming interfaces
+C 03  Display controller
+\t00  VGA compatible controller
+";
+
+    #[test]
+    fn vendor_and_device_are_found() {
+        assert_eq!(
+            lookup_pci_ids(PCI_IDS, 0x10de, 0x2484),
+            (
+                Some("NVIDIA Corporation".to_string()),
+                Some("GA104 [GeForce RTX 3070]".to_string())
+            )
+        );
+        assert_eq!(
+            lookup_pci_ids(PCI_IDS, VENDOR_INTEL, 0x5917),
+            (
+                Some("Intel Corporation".to_string()),
+                Some("UHD Graphics 620".to_string())
+            )
+        );
+    }
+
+    #[test]
+    fn subsystem_lines_are_not_devices() {
+        assert_eq!(
+            lookup_pci_ids(PCI_IDS, 0x1002, 0x0e3a),
+            (
+                Some("Advanced Micro Devices, Inc. [AMD/ATI]".to_string()),
+                None
+            )
+        );
+    }
+
+    #[test]
+    fn devices_of_the_next_vendor_do_not_match() {
+        assert_eq!(
+            lookup_pci_ids(PCI_IDS, VENDOR_INTEL, 0x0aaa),
+            (Some("Intel Corporation".to_string()), None)
+        );
+    }
+
+    #[test]
+    fn unknown_vendors_and_device_classes_are_not_matched() {
+        assert_eq!(lookup_pci_ids(PCI_IDS, 0x1234, 0x0000), (None, None));
+        assert_eq!(lookup_pci_ids(PCI_IDS, 0x0300, 0x0000), (None, None));
+        assert_eq!(lookup_pci_ids("", 0x10de, 0x2484), (None, None));
+    }
+
+    #[test]
+    fn names_use_the_bracketed_model_and_short_vendor() {
+        assert_eq!(pci_name(PCI_IDS, 0x10de, 0x2484), "NVIDIA GeForce RTX 3070");
+        assert_eq!(
+            pci_name(PCI_IDS, VENDOR_INTEL, 0x5917),
+            "Intel UHD Graphics 620"
+        );
+        assert_eq!(pci_name(PCI_IDS, VENDOR_INTEL, 0x56a0), "Intel Arc A770");
+        assert_eq!(pci_name(PCI_IDS, 0x10de, 0x1234), "NVIDIA [10de:1234]");
+        assert_eq!(pci_name("", 0x1af4, 0x1050), "1af4 [1af4:1050]");
+    }
+
+    #[test]
+    fn vendor_is_not_repeated_when_the_model_names_it() {
+        assert_eq!(
+            pci_name(PCI_IDS, VENDOR_INTEL, 0x7d55),
+            "Intel Arc Graphics"
+        );
+    }
+
+    #[test]
+    fn intel_kind_comes_from_the_bus() {
+        // Meteor Lake's "Intel Arc Graphics" is integrated
+        assert_eq!(intel_kind("0000:00:02.0"), Some(GpuKind::Integrated));
+        assert_eq!(intel_kind("0000:03:00.0"), Some(GpuKind::Discrete));
+        assert_eq!(intel_kind("platform"), None);
+    }
+}
@@ -0,0 +1,96 @@
+use super::{Collector, Registry};
+use crate::system_info::{is_android, is_termux, InfoValue};
+use std::sync::Arc;
+
+pub fn register(registry: &mut Registry) {
+    registry.register(Arc::new(Username));
+    registry.register(Arc::new(Hostname));
+    registry.register(Arc::new(Environment));
+    registry.register(Arc::new(DesktopEnvironment));
+    registry.register(Arc::new(DisplayServer));
+}
+
+struct Username;
+
+impl Collector for Username {
+    fn key(&self) -> &'static str {
+        "Username"
+    }
+
+    fn collect(&self) -> Option<InfoValue> {
+        Some(InfoValue::Text(whoami::username()))
+    }
+}
+
+struct Hostname;
+
+impl Collector for Hostname {
+    fn key(&self) -> &'static str {
+        "Hostname"
+    }
+
+    fn collect(&self) -> Option<InfoValue> {
+        Some(InfoValue::Text(
+            whoami::fallible::hostname().unwrap_or_else(|_| "Unknown".to_string()),
+        ))
+    }
+}
+
+/// Only reported on Android, where there is no desktop environment.
+struct Environment;
+
+impl Collector for Environment {
+    fn key(&self) -> &'static str {
+        "Environment"
+    }
+
+    fn collect(&self) -> Option<InfoValue> {
+        if is_termux() {
+            Some(InfoValue::Text("Termux (Android)".to_string()))
+        } else if is_android() {
+            Some(InfoValue::Text("Android".to_string()))
+        } else {
+            None
+        }
+    }
+}
+
+struct DesktopEnvironment;
+
+impl Collector for DesktopEnvironment {
+    fn key(&self) -> &'static str {
+        "Desktop Environment"
+    }
+
+    fn collect(&self) -> Option<InfoValue> {
+        if is_termux() || is_android() {
+            return None;
+        }
+        Some(InfoValue::Text(
+            std::env::var("XDG_CURRENT_DESKTOP")
+                .or_else(|_| std::env::var("DESKTOP_SESSION"))
+                .unwrap_or_else(|_| "Unknown".to_string()),
+        ))
+    }
+}
+
+struct DisplayServer;
+
+impl Collector for DisplayServer {
+    fn key(&self) -> &'static str {
+        "Display Server"
+    }
+
+    fn collect(&self) -> Option<InfoValue> {
+        let server = if is_termux() || is_android() {
+            "Android SurfaceFlinger"
+        } else if std::env::var("WAYLAND_DISPLAY").is_ok() {
+            "Wayland"
+        } else if std::env::var("DISPLAY").is_ok() {
+            "Xorg"
+        } else {
+            "Unknown Display Server"
+        };
+        Some(InfoValue::Text(server.to_string()))
+    }
+}
@@ -0,0 +1,26 @@
+use super::{Collector, Registry};
+use crate::system_info::InfoValue;
+use std::process::Command;
+use std::sync::Arc;
+
+pub fn register(registry: &mut Registry) {
+    registry.register(Arc::new(KernelVersion));
+}
+
+struct KernelVersion;
+
+impl Collector for KernelVersion {
+    fn key(&self) -> &'static str {
+        "Kernel Version"
+    }
+
+    fn collect(&self) -> Option<InfoValue> {
+        Some(InfoValue::Text(
+            Command::new("uname")
+                .arg("-r")
+                .output()
+                .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+                .unwrap_or_else(|_| "Unknown Kernel Version".to_string()),
+        ))
+    }
+}
@@ -0,0 +1,122 @@
+use super::{module_integer, Collector, Registry};
+use crate::system_info::{InfoValue, LoadAverage, ProcessCount};
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use sysinfo::{CpuExt, System, SystemExt};
+use toml::Value;
+
+/// Used when `[modules."CPU Usage"]` has no `interval_ms`.
+const DEFAULT_INTERVAL_MS: u64 = 250;
+
+pub fn register(registry: &mut Registry, config: &Value) {
+    let interval = module_integer(config, "CPU Usage", "interval_ms")
+        .map(|ms| Duration::from_millis(ms.max(0) as u64))
+        .unwrap_or(Duration::from_millis(DEFAULT_INTERVAL_MS));
+    registry.register(Arc::new(LoadAvg));
+    registry.register(Arc::new(Processes));
+    registry.register(Arc::new(CpuUsage { interval }));
+}
+
+/// The 1, 5 and 15 minute load averages.
+struct LoadAvg;
+
+impl Collector for LoadAvg {
+    fn key(&self) -> &'static str {
+        "LoadAvg"
+    }
+
+    fn collect(&self) -> Option<InfoValue> {
+        // "0.52 0.58 0.59 2/1043 12345"
+        let load = match fs::read_to_string("/proc/loadavg") {
+            Ok(contents) => {
+                let mut fields = contents.split_whitespace().map(|field| field.parse().ok());
+                LoadAverage {
+                    one: fields.next()??,
+                    five: fields.next()??,
+                    fifteen: fields.next()??,
+                }
+            }
+            Err(_) => {
+                let load = System::new().load_average();
+                LoadAverage {
+                    one: load.one,
+                    five: load.five,
+                    fifteen: load.fifteen,
+                }
+            }
+        };
+        Some(InfoValue::LoadAverage(load))
+    }
+}
+
+/// How many processes exist and how many of them are running. The counts in
+/// /proc/loadavg include threads, so the process directories are read instead;
+/// a process is running when any of its threads is.
+struct Processes;
+
+impl Collector for Processes {
+    fn key(&self) -> &'static str {
+        "Processes"
+    }
+
+    fn collect(&self) -> Option<InfoValue> {
+        let mut total = 0;
+        let mut running = 0;
+        for entry in fs::read_dir("/proc").ok()?.filter_map(|entry| entry.ok()) {
+            if entry.file_name().to_string_lossy().parse::<u32>().is_err() {
+                continue;
+            }
+            // A process may exit between listing and reading it
+            let Ok(stat) = fs::read_to_string(entry.path().join("stat")) else {
+                continue;
+            };
+            total += 1;
+            if is_running(&stat) || any_thread_running(&entry.path()) {
+                running += 1;
+            }
+        }
+        (total > 0).then_some(InfoValue::Processes(ProcessCount { total, running }))
+    }
+}
+
+/// The state in a stat file, which follows the parenthesized command name.
+fn is_running(stat: &str) -> bool {
+    stat.rfind(')')
+        .and_then(|end| stat[end + 1..].split_whitespace().next())
+        == Some("R")
+}
+
+fn any_thread_running(process: &Path) -> bool {
+    fs::read_dir(process.join("task"))
+        .map(|tasks| {
+            tasks
+                .filter_map(|task| task.ok())
+                .filter_map(|task| fs::read_to_string(task.path().join("stat")).ok())
+                .any(|stat| is_running(&stat))
+        })
+        .unwrap_or(false)
+}
+
+/// Overall CPU usage, measured between two samples `interval` apart.
+struct CpuUsage {
+    interval: Duration,
+}
+
+impl Collector for CpuUsage {
+    fn key(&self) -> &'static str {
+        "CPU Usage"
+    }
+
+    fn collect(&self) -> Option<InfoValue> {
+        let mut system = System::new();
+        system.refresh_cpu();
+        // Shorter intervals than sysinfo's minimum give meaningless numbers
+        std::thread::sleep(self.interval.max(System::MINIMUM_CPU_UPDATE_INTERVAL));
+        system.refresh_cpu();
+        Some(InfoValue::CpuUsage(
+            system.global_cpu_info().cpu_usage() as f64
+        ))
+    }
+}
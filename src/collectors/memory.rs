@@ -0,0 +1,25 @@
+use super::{Collector, Registry};
+use crate::system_info::{InfoValue, MemoryInfo};
+use std::sync::Arc;
+use sysinfo::{System, SystemExt};
+
+pub fn register(registry: &mut Registry) {
+    registry.register(Arc::new(Memory));
+}
+
+struct Memory;
+
+impl Collector for Memory {
+    fn key(&self) -> &'static str {
+        "Memory"
+    }
+
+    fn collect(&self) -> Option<InfoValue> {
+        let mut system = System::new();
+        system.refresh_memory();
+        Some(InfoValue::Memory(MemoryInfo {
+            used_bytes: system.used_memory(),
+            total_bytes: system.total_memory(),
+        }))
+    }
+}
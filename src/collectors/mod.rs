@@ -0,0 +1,266 @@
+// Collectors probe one piece of system information each. Every collector is
+// registered under the info key it provides, so only the keys listed in
+// `output.info_keys` are ever probed. Collectors run concurrently, each bounded
+// by the timeout configured in the `[modules]` table.
+
+mod battery;
+mod cpu;
+mod desktop;
+mod disk;
+mod display;
+mod gpu;
+mod host;
+mod kernel;
+mod load;
+mod memory;
+mod network;
+mod os;
+mod packages;
+mod shell;
+mod uptime;
+mod wayland;
+
+use crate::system_info::{InfoValue, SystemInfo};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::sync::Arc;
+use std::time::Duration;
+use toml::Value;
+
+/// Where the kernel lists GPUs (`card0`) and their connectors (`card0-DP-1`).
+const DRM_CLASS: &str = "/sys/class/drm";
+
+/// Used when neither `[modules]` nor the module's own table sets `timeout_ms`.
+const DEFAULT_TIMEOUT_MS: u64 = 2000;
+
+pub trait Collector: Send + Sync {
+    /// The info key this collector provides, as used in `output.info_keys`.
+    fn key(&self) -> &'static str;
+
+    /// Probes the system. Returns `None` when the value does not apply here.
+    fn collect(&self) -> Option<InfoValue>;
+}
+
+/// Collectors keyed by the info key they provide, in registration order.
+pub struct Registry {
+    collectors: Vec<Arc<dyn Collector>>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Registry {
+            collectors: Vec::new(),
+        }
+    }
+
+    /// A registry holding every collector that ships with piko. Collectors
+    /// with options read them from their `[modules."Key"]` table in `config`.
+    pub fn builtin(config: &Value) -> Self {
+        let mut registry = Registry::new();
+        host::register(&mut registry);
+        desktop::register(&mut registry);
+        os::register(&mut registry, config);
+        kernel::register(&mut registry);
+        uptime::register(&mut registry);
+        packages::register(&mut registry);
+        shell::register(&mut registry);
+        cpu::register(&mut registry, config);
+        load::register(&mut registry, config);
+        memory::register(&mut registry);
+        gpu::register(&mut registry);
+        display::register(&mut registry);
+        battery::register(&mut registry);
+        network::register(&mut registry, config);
+        disk::register(&mut registry);
+        registry
+    }
+
+    /// Registers a collector, replacing any previous one for the same key.
+    pub fn register(&mut self, collector: Arc<dyn Collector>) {
+        match self
+            .collectors
+            .iter_mut()
+            .find(|existing| existing.key() == collector.key())
+        {
+            Some(existing) => *existing = collector,
+            None => self.collectors.push(collector),
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Arc<dyn Collector>> {
+        self.collectors
+            .iter()
+            .find(|collector| collector.key() == key)
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.collectors.iter().map(|collector| collector.key())
+    }
+
+    /// Runs the collectors for `keys` concurrently and returns their values in
+    /// the order of `keys`. Unknown keys are skipped, and a collector that
+    /// exceeds its timeout is reported as `InfoValue::TimedOut`.
+    pub fn collect(&self, keys: &[String], timeouts: &Timeouts) -> SystemInfo {
+        let mut collectors: Vec<Arc<dyn Collector>> = Vec::new();
+        for key in keys {
+            if let Some(collector) = self.get(key) {
+                if !collectors.iter().any(|c| c.key() == collector.key()) {
+                    collectors.push(Arc::clone(collector));
+                }
+            }
+        }
+
+        let runtime = match tokio::runtime::Builder::new_multi_thread()
+            .enable_time()
+            .build()
+        {
+            Ok(runtime) => runtime,
+            Err(_) => return collect_sequentially(&collectors),
+        };
+
+        let results = runtime.block_on(async {
+            // Spawn everything first so that all timeouts start together.
+            let tasks: Vec<_> = collectors
+                .iter()
+                .map(|collector| {
+                    let collector = Arc::clone(collector);
+                    let timeout = timeouts.for_key(collector.key());
+                    tokio::spawn(async move {
+                        let probe = tokio::task::spawn_blocking(move || collector.collect());
+                        match tokio::time::timeout(timeout, probe).await {
+                            Ok(Ok(value)) => value,
+                            // The collector panicked; treat it as unavailable.
+                            Ok(Err(_)) => None,
+                            Err(_) => Some(InfoValue::TimedOut),
+                        }
+                    })
+                })
+                .collect();
+
+            let mut results = Vec::with_capacity(tasks.len());
+            for task in tasks {
+                results.push(task.await.ok().flatten());
+            }
+            results
+        });
+
+        // Don't wait for probes that are still stuck in a subprocess.
+        runtime.shutdown_background();
+
+        let mut info = SystemInfo::default();
+        for (collector, value) in collectors.iter().zip(results) {
+            if let Some(value) = value {
+                info.insert(collector.key(), value);
+            }
+        }
+        info
+    }
+}
+
+fn collect_sequentially(collectors: &[Arc<dyn Collector>]) -> SystemInfo {
+    let mut info = SystemInfo::default();
+    for collector in collectors {
+        if let Some(value) = collector.collect() {
+            info.insert(collector.key(), value);
+        }
+    }
+    info
+}
+
+/// Per-module time limits, read from the `[modules]` table:
+///
+/// ```toml
+/// [modules]
+/// timeout_ms = 2000
+///
+/// [modules."GPU Model"]
+/// timeout_ms = 5000
+/// ```
+pub struct Timeouts {
+    default: Duration,
+    per_key: HashMap<String, Duration>,
+}
+
+impl Timeouts {
+    pub fn from_config(config: &Value) -> Self {
+        let modules = config.get("modules").and_then(|modules| modules.as_table());
+
+        let default = modules
+            .and_then(timeout_ms)
+            .unwrap_or(Duration::from_millis(DEFAULT_TIMEOUT_MS));
+
+        let per_key = modules
+            .map(|modules| {
+                modules
+                    .iter()
+                    .filter_map(|(key, value)| {
+                        let timeout = value.as_table().and_then(timeout_ms)?;
+                        Some((key.clone(), timeout))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Timeouts { default, per_key }
+    }
+
+    pub fn for_key(&self, key: &str) -> Duration {
+        self.per_key.get(key).copied().unwrap_or(self.default)
+    }
+}
+
+/// A string option from the module's own table, e.g. `format` in `[modules.OS]`.
+fn module_str(config: &Value, key: &str, option: &str) -> Option<String> {
+    config
+        .get("modules")
+        .and_then(|modules| modules.get(key))
+        .and_then(|module| module.get(option))
+        .and_then(|v| v.as_str())
+        .map(String::from)
+}
+
+fn module_bool(config: &Value, key: &str, option: &str) -> Option<bool> {
+    config
+        .get("modules")
+        .and_then(|modules| modules.get(key))
+        .and_then(|module| module.get(option))
+        .and_then(|v| v.as_bool())
+}
+
+fn module_integer(config: &Value, key: &str, option: &str) -> Option<i64> {
+    config
+        .get("modules")
+        .and_then(|modules| modules.get(key))
+        .and_then(|module| module.get(option))
+        .and_then(|v| v.as_integer())
+}
+
+/// The stdout of a command that ran successfully.
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// A sysfs or procfs attribute without its trailing newline.
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path)
+        .ok()
+        .map(|value| value.trim().to_string())
+}
+
+fn read_number(path: &Path) -> Option<u64> {
+    read_trimmed(path)?.parse().ok()
+}
+
+fn timeout_ms(table: &toml::value::Table) -> Option<Duration> {
+    table
+        .get("timeout_ms")
+        .and_then(|v| v.as_integer())
+        .filter(|ms| *ms >= 0)
+        .map(|ms| Duration::from_millis(ms as u64))
+}
@@ -0,0 +1,417 @@
+use super::{command_output, module_bool, read_number, read_trimmed, Collector, Registry};
+use crate::system_info::{InfoValue, InterfaceAddr, InterfaceInfo};
+use std::fs;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use toml::Value;
+
+const NET_CLASS: &str = "/sys/class/net";
+
+/// `ARPHRD_LOOPBACK` in /sys/class/net/*/type.
+const TYPE_LOOPBACK: u32 = 772;
+/// `IFF_UP` in /sys/class/net/*/flags.
+const FLAG_UP: u32 = 0x1;
+
+pub fn register(registry: &mut Registry, config: &Value) {
+    let option = |name| module_bool(config, "Network", name).unwrap_or(false);
+    registry.register(Arc::new(Network {
+        show_loopback: option("show_loopback"),
+        show_docker: option("show_docker"),
+        show_veth: option("show_veth"),
+        hide_mac: option("hide_mac"),
+    }));
+}
+
+/// Every interface that is up and has an address, one per line, with its
+/// MAC address, link speed and the SSID of a wireless link.
+struct Network {
+    show_loopback: bool,
+    show_docker: bool,
+    show_veth: bool,
+    hide_mac: bool,
+}
+
+impl Collector for Network {
+    fn key(&self) -> &'static str {
+        "Network"
+    }
+
+    fn collect(&self) -> Option<InfoValue> {
+        let addresses = interface_addresses();
+        let interfaces: Vec<InterfaceInfo> = interfaces()
+            .iter()
+            .filter(|interface| self.is_shown(interface))
+            .filter_map(|interface| self.describe(interface, &addresses))
+            .collect();
+        (!interfaces.is_empty()).then_some(InfoValue::Interfaces(interfaces))
+    }
+}
+
+impl Network {
+    fn is_shown(&self, interface: &Path) -> bool {
+        let name = file_name(interface);
+        let flags = read_trimmed(&interface.join("flags"))
+            .and_then(|flags| u32::from_str_radix(flags.trim_start_matches("0x"), 16).ok())
+            .unwrap_or(0);
+        // Loopback and tunnel devices have no carrier to report and stay "unknown"
+        let operstate = read_trimmed(&interface.join("operstate")).unwrap_or_default();
+        if flags & FLAG_UP == 0 || !matches!(operstate.as_str(), "up" | "unknown") {
+            return false;
+        }
+
+        let is_loopback = read_number(&interface.join("type")) == Some(TYPE_LOOPBACK as u64);
+        // Docker names its default bridge docker0 and user networks br-<id>
+        let is_docker = name.starts_with("docker") || name.starts_with("br-");
+        let is_veth = name.starts_with("veth");
+        (self.show_loopback || !is_loopback)
+            && (self.show_docker || !is_docker)
+            && (self.show_veth || !is_veth)
+    }
+
+    fn describe(&self, interface: &Path, addresses: &[(u32, IpAddr, u8)]) -> Option<InterfaceInfo> {
+        let index = read_number(&interface.join("ifindex"))? as u32;
+        let mut addrs: Vec<InterfaceAddr> = addresses
+            .iter()
+            .filter(|(address_index, address, _)| {
+                *address_index == index && !is_link_local(address)
+            })
+            .map(|(_, addr, prefix)| InterfaceAddr {
+                addr: *addr,
+                prefix: *prefix,
+            })
+            .collect();
+        if addrs.is_empty() {
+            return None;
+        }
+        // IPv4 first
+        addrs.sort_by_key(|address| address.addr.is_ipv6());
+
+        let mac = read_trimmed(&interface.join("address"))
+            .filter(|mac| !self.hide_mac && !mac.is_empty() && mac != "00:00:00:00:00:00");
+        // Virtual and wireless interfaces report -1 or fail to read
+        let speed_mbps = read_trimmed(&interface.join("speed"))
+            .and_then(|speed| speed.parse::<i64>().ok())
+            .filter(|speed| *speed > 0)
+            .map(|speed| speed as u64);
+
+        Some(InterfaceInfo {
+            name: file_name(interface),
+            addrs,
+            mac,
+            speed_mbps,
+            ssid: ssid(interface),
+        })
+    }
+}
+
+fn interfaces() -> Vec<PathBuf> {
+    let mut interfaces: Vec<PathBuf> = fs::read_dir(NET_CLASS)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .collect()
+        })
+        .unwrap_or_default();
+    interfaces.sort();
+    interfaces
+}
+
+/// Every address with its interface index and prefix length. Netlink is the
+/// only way to get IPv4 addresses without ioctls; where it is denied, as for
+/// apps on recent Android, the IPv6 ones are still in /proc/net/if_inet6.
+fn interface_addresses() -> Vec<(u32, IpAddr, u8)> {
+    netlink_addresses().unwrap_or_else(proc_inet6_addresses)
+}
+
+/// Lines like "fe800000000000000211223344556677 02 40 20 80 wlan0": the
+/// address, interface index, prefix length, scope, flags and name, in hex.
+fn proc_inet6_addresses() -> Vec<(u32, IpAddr, u8)> {
+    let contents = fs::read_to_string("/proc/net/if_inet6").unwrap_or_default();
+    contents
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let address = u128::from_str_radix(fields.next()?, 16).ok()?;
+            let index = u32::from_str_radix(fields.next()?, 16).ok()?;
+            let prefix = u8::from_str_radix(fields.next()?, 16).ok()?;
+            Some((index, IpAddr::V6(Ipv6Addr::from(address)), prefix))
+        })
+        .collect()
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn netlink_addresses() -> Option<Vec<(u32, IpAddr, u8)>> {
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+
+    const NLMSG_HEADER_LEN: usize = 16;
+    const NLMSG_ERROR: u16 = 2;
+    const NLMSG_DONE: u16 = 3;
+    const RTM_NEWADDR: u16 = 20;
+    const RTM_GETADDR: u16 = 22;
+    const NLM_F_REQUEST: u16 = 0x1;
+    const NLM_F_DUMP: u16 = 0x300;
+
+    // SAFETY: socket() has no preconditions.
+    let fd = unsafe {
+        libc::socket(
+            libc::AF_NETLINK,
+            libc::SOCK_RAW | libc::SOCK_CLOEXEC,
+            libc::NETLINK_ROUTE,
+        )
+    };
+    if fd < 0 {
+        return None;
+    }
+    // SAFETY: `fd` was just opened and nothing else owns it; `socket` closes it
+    // on every return.
+    let socket = unsafe { OwnedFd::from_raw_fd(fd) };
+
+    // An nlmsghdr followed by an ifaddrmsg asking for every address family
+    let mut request = Vec::with_capacity(NLMSG_HEADER_LEN + 8);
+    request.extend_from_slice(&((NLMSG_HEADER_LEN + 8) as u32).to_ne_bytes());
+    request.extend_from_slice(&RTM_GETADDR.to_ne_bytes());
+    request.extend_from_slice(&(NLM_F_REQUEST | NLM_F_DUMP).to_ne_bytes());
+    request.extend_from_slice(&1u32.to_ne_bytes());
+    request.extend_from_slice(&0u32.to_ne_bytes());
+    request.extend_from_slice(&[libc::AF_UNSPEC as u8, 0, 0, 0, 0, 0, 0, 0]);
+
+    // SAFETY: `request` outlives the call and its length is passed along.
+    let sent = unsafe {
+        libc::send(
+            socket.as_raw_fd(),
+            request.as_ptr().cast(),
+            request.len(),
+            0,
+        )
+    };
+    if sent != request.len() as isize {
+        return None;
+    }
+
+    let mut addresses = Vec::new();
+    let mut buffer = vec![0u8; 32 * 1024];
+    loop {
+        // SAFETY: `buffer` outlives the call, and recv() writes at most its
+        // length into it.
+        let received = unsafe {
+            libc::recv(
+                socket.as_raw_fd(),
+                buffer.as_mut_ptr().cast(),
+                buffer.len(),
+                0,
+            )
+        };
+        if received <= 0 {
+            return None;
+        }
+
+        let mut messages = &buffer[..received as usize];
+        while messages.len() >= NLMSG_HEADER_LEN {
+            let len = u32::from_ne_bytes(messages[0..4].try_into().ok()?) as usize;
+            let kind = u16::from_ne_bytes(messages[4..6].try_into().ok()?);
+            if len < NLMSG_HEADER_LEN || len > messages.len() {
+                return None;
+            }
+            match kind {
+                NLMSG_DONE => return Some(addresses),
+                NLMSG_ERROR => return None,
+                RTM_NEWADDR => addresses.extend(parse_ifaddrmsg(&messages[NLMSG_HEADER_LEN..len])),
+                _ => {}
+            }
+            messages = &messages[align4(len).min(messages.len())..];
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn netlink_addresses() -> Option<Vec<(u32, IpAddr, u8)>> {
+    None
+}
+
+/// An ifaddrmsg (family, prefix length, flags, scope, interface index)
+/// followed by attributes, each a length, a type and the payload.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn parse_ifaddrmsg(message: &[u8]) -> Option<(u32, IpAddr, u8)> {
+    const IFA_ADDRESS: u16 = 1;
+    const IFA_LOCAL: u16 = 2;
+
+    let family = *message.first()? as i32;
+    let prefix = *message.get(1)?;
+    let index = u32::from_ne_bytes(message.get(4..8)?.try_into().ok()?);
+
+    let mut address = None;
+    let mut local = None;
+    let mut attributes = message.get(8..)?;
+    while attributes.len() >= 4 {
+        let len = u16::from_ne_bytes([attributes[0], attributes[1]]) as usize;
+        let kind = u16::from_ne_bytes([attributes[2], attributes[3]]);
+        if len < 4 || len > attributes.len() {
+            break;
+        }
+        let payload = &attributes[4..len];
+        let ip = match family {
+            libc::AF_INET => <[u8; 4]>::try_from(payload)
+                .ok()
+                .map(|octets| IpAddr::V4(Ipv4Addr::from(octets))),
+            libc::AF_INET6 => <[u8; 16]>::try_from(payload)
+                .ok()
+                .map(|octets| IpAddr::V6(Ipv6Addr::from(octets))),
+            _ => None,
+        };
+        match kind {
+            IFA_ADDRESS => address = ip,
+            IFA_LOCAL => local = ip,
+            _ => {}
+        }
+        attributes = &attributes[align4(len).min(attributes.len())..];
+    }
+    // On point-to-point links IFA_ADDRESS is the peer and IFA_LOCAL our own
+    Some((index, local.or(address)?, prefix))
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn align4(len: usize) -> usize {
+    (len + 3) & !3
+}
+
+/// fe80::/10 addresses exist on every IPv6 interface and say little.
+fn is_link_local(address: &IpAddr) -> bool {
+    match address {
+        IpAddr::V6(address) => address.segments()[0] & 0xffc0 == 0xfe80,
+        IpAddr::V4(_) => false,
+    }
+}
+
+/// The network a wireless interface is associated with, asked from `iw` or
+/// the older wireless tools. Reading it needs no privileges.
+fn ssid(interface: &Path) -> Option<String> {
+    if !interface.join("wireless").exists() && !interface.join("phy80211").exists() {
+        return None;
+    }
+    let name = file_name(interface);
+    let link = command_output("iw", &["dev", &name, "link"]).and_then(|output| {
+        output
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("SSID:"))
+            .map(|ssid| ssid.trim().to_string())
+    });
+    link.or_else(|| command_output("iwgetid", &["-r", &name]).map(|ssid| ssid.trim().to_string()))
+        .filter(|ssid| !ssid.is_empty())
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+#[cfg(all(test, any(target_os = "linux", target_os = "android")))]
+mod tests {
+    use super::*;
+
+    const IFA_ADDRESS: u16 = 1;
+    const IFA_LOCAL: u16 = 2;
+    const IFA_LABEL: u16 = 3;
+
+    fn ifaddrmsg(family: i32, prefix: u8, index: u32) -> Vec<u8> {
+        let mut message = vec![family as u8, prefix, 0, 0];
+        message.extend_from_slice(&index.to_ne_bytes());
+        message
+    }
+
+    /// Appends an attribute, padded to 4 bytes like the kernel does.
+    fn attribute(message: &mut Vec<u8>, kind: u16, payload: &[u8]) {
+        message.extend_from_slice(&((4 + payload.len()) as u16).to_ne_bytes());
+        message.extend_from_slice(&kind.to_ne_bytes());
+        message.extend_from_slice(payload);
+        message.resize(align4(message.len()), 0);
+    }
+
+    #[test]
+    fn ipv4_address() {
+        let mut message = ifaddrmsg(libc::AF_INET, 24, 2);
+        attribute(&mut message, IFA_ADDRESS, &[192, 168, 1, 20]);
+        assert_eq!(
+            parse_ifaddrmsg(&message),
+            Some((2, IpAddr::V4(Ipv4Addr::new(192, 168, 1, 20)), 24))
+        );
+    }
+
+    #[test]
+    fn local_wins_over_the_peer_on_point_to_point_links() {
+        let mut message = ifaddrmsg(libc::AF_INET, 32, 5);
+        attribute(&mut message, IFA_ADDRESS, &[10, 8, 0, 1]);
+        attribute(&mut message, IFA_LOCAL, &[10, 8, 0, 2]);
+        assert_eq!(
+            parse_ifaddrmsg(&message),
+            Some((5, IpAddr::V4(Ipv4Addr::new(10, 8, 0, 2)), 32))
+        );
+    }
+
+    #[test]
+    fn ipv6_address() {
+        let address = "2001:db8::1".parse::<Ipv6Addr>().unwrap();
+        let mut message = ifaddrmsg(libc::AF_INET6, 64, 3);
+        attribute(&mut message, IFA_ADDRESS, &address.octets());
+        assert_eq!(
+            parse_ifaddrmsg(&message),
+            Some((3, IpAddr::V6(address), 64))
+        );
+    }
+
+    #[test]
+    fn attributes_are_stepped_over_at_four_byte_boundaries() {
+        // "lo" plus its NUL takes 7 bytes and is padded to 8
+        let mut message = ifaddrmsg(libc::AF_INET, 8, 1);
+        attribute(&mut message, IFA_LABEL, b"lo\0");
+        assert_eq!(message.len(), 16);
+        attribute(&mut message, IFA_LOCAL, &[127, 0, 0, 1]);
+        assert_eq!(
+            parse_ifaddrmsg(&message),
+            Some((1, IpAddr::V4(Ipv4Addr::LOCALHOST), 8))
+        );
+
+        // The last attribute may lack its padding
+        let mut message = ifaddrmsg(libc::AF_INET, 8, 1);
+        attribute(&mut message, IFA_LOCAL, &[127, 0, 0, 1]);
+        attribute(&mut message, IFA_LABEL, b"lo\0");
+        message.pop();
+        assert_eq!(
+            parse_ifaddrmsg(&message),
+            Some((1, IpAddr::V4(Ipv4Addr::LOCALHOST), 8))
+        );
+        assert_eq!(align4(7), 8);
+        assert_eq!(align4(8), 8);
+    }
+
+    #[test]
+    fn payloads_of_the_wrong_size_are_ignored() {
+        let mut message = ifaddrmsg(libc::AF_INET, 24, 2);
+        attribute(&mut message, IFA_ADDRESS, &[192, 168, 1]);
+        assert_eq!(parse_ifaddrmsg(&message), None);
+    }
+
+    #[test]
+    fn truncated_and_zero_length_attributes_end_the_parse() {
+        // Claims 8 bytes but only 6 are left
+        let mut message = ifaddrmsg(libc::AF_INET, 24, 2);
+        attribute(&mut message, IFA_LOCAL, &[192, 168, 1, 20]);
+        message.extend_from_slice(&8u16.to_ne_bytes());
+        message.extend_from_slice(&IFA_ADDRESS.to_ne_bytes());
+        message.extend_from_slice(&[10, 0]);
+        assert_eq!(
+            parse_ifaddrmsg(&message),
+            Some((2, IpAddr::V4(Ipv4Addr::new(192, 168, 1, 20)), 24))
+        );
+
+        // A zero length would otherwise never advance
+        let mut message = ifaddrmsg(libc::AF_INET, 24, 2);
+        message.extend_from_slice(&[0, 0, 0, 0]);
+        attribute(&mut message, IFA_LOCAL, &[192, 168, 1, 20]);
+        assert_eq!(parse_ifaddrmsg(&message), None);
+
+        // Too short for the ifaddrmsg itself
+        assert_eq!(parse_ifaddrmsg(&[libc::AF_INET as u8, 24, 0]), None);
+    }
+}
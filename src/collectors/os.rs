@@ -0,0 +1,171 @@
+use super::{module_str, Collector, Registry};
+use crate::os_release::OsRelease;
+use crate::system_info::{is_android, is_termux, InfoValue};
+use std::collections::HashMap;
+use std::fs;
+use std::process::Command;
+use std::sync::Arc;
+use toml::Value;
+
+/// Used when `[modules.OS]` has no `format`.
+const DEFAULT_FORMAT: &str = "{pretty_name} {arch}";
+
+pub fn register(registry: &mut Registry, config: &Value) {
+    let format = module_str(config, "OS", "format").unwrap_or_else(|| DEFAULT_FORMAT.to_string());
+    registry.register(Arc::new(Os { format }));
+}
+
+/// Reports the distribution through `format`, whose placeholders are
+/// `{pretty_name}`, `{name}`, `{version}`, `{version_id}`, `{id}`,
+/// `{codename}` and `{arch}`.
+struct Os {
+    format: String,
+}
+
+impl Collector for Os {
+    fn key(&self) -> &'static str {
+        "OS"
+    }
+
+    fn collect(&self) -> Option<InfoValue> {
+        let os = if is_termux() {
+            "Android (Termux)".to_string()
+        } else if is_android() {
+            "Android".to_string()
+        } else if cfg!(target_os = "linux") {
+            match linux_release() {
+                Some(fields) => format_fields(&self.format, &fields),
+                None => Command::new("uname")
+                    .arg("-o")
+                    .output()
+                    .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+                    .unwrap_or_else(|_| "Unknown OS".to_string()),
+            }
+        } else if cfg!(target_os = "macos") {
+            let sw_vers = |flag: &str| {
+                Command::new("sw_vers")
+                    .arg(flag)
+                    .output()
+                    .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+                    .unwrap_or_default()
+            };
+            let name = sw_vers("-productName");
+            if name.is_empty() {
+                "Unknown OS".to_string()
+            } else {
+                let version = sw_vers("-productVersion");
+                let fields = HashMap::from([
+                    ("pretty_name", format!("{} {}", name, version)),
+                    ("name", name),
+                    ("version", version.clone()),
+                    ("version_id", version),
+                    ("id", "macos".to_string()),
+                ]);
+                format_fields(&self.format, &fields)
+            }
+        } else {
+            "Unsupported OS".to_string()
+        };
+        Some(InfoValue::Text(os))
+    }
+}
+
+/// The release fields of a Linux distribution, read from os-release, then
+/// lsb-release, then the first line of any other /etc/*-release file.
+fn linux_release() -> Option<HashMap<&'static str, String>> {
+    if let Some(os_release) = OsRelease::load() {
+        let name = os_release.name().unwrap_or("Linux");
+        let version_id = os_release.version_id().unwrap_or_default();
+        let pretty_name = os_release
+            .pretty_name()
+            .map(String::from)
+            .unwrap_or_else(|| format!("{} {}", name, version_id));
+        return Some(HashMap::from([
+            ("pretty_name", pretty_name),
+            ("name", name.to_string()),
+            (
+                "version",
+                os_release.get("VERSION").unwrap_or_default().to_string(),
+            ),
+            ("version_id", version_id.to_string()),
+            ("id", os_release.id().unwrap_or("linux").to_string()),
+            (
+                "codename",
+                os_release
+                    .get("VERSION_CODENAME")
+                    .unwrap_or_default()
+                    .to_string(),
+            ),
+        ]));
+    }
+
+    if let Ok(contents) = fs::read_to_string("/etc/lsb-release") {
+        // Same KEY=value syntax as os-release, with different keys
+        let lsb = OsRelease::parse(&contents);
+        if let Some(name) = lsb.get("DISTRIB_ID") {
+            let release = lsb.get("DISTRIB_RELEASE").unwrap_or_default();
+            let pretty_name = lsb
+                .get("DISTRIB_DESCRIPTION")
+                .map(String::from)
+                .unwrap_or_else(|| format!("{} {}", name, release));
+            return Some(HashMap::from([
+                ("pretty_name", pretty_name),
+                ("name", name.to_string()),
+                ("version", release.to_string()),
+                ("version_id", release.to_string()),
+                ("id", name.to_lowercase()),
+                (
+                    "codename",
+                    lsb.get("DISTRIB_CODENAME").unwrap_or_default().to_string(),
+                ),
+            ]));
+        }
+    }
+
+    // e.g. /etc/redhat-release: "CentOS release 6.10 (Final)"
+    let mut release_files: Vec<_> = fs::read_dir("/etc")
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.ends_with("-release") && name != "lsb-release")
+        })
+        .collect();
+    release_files.sort();
+    release_files.into_iter().find_map(|path| {
+        let contents = fs::read_to_string(&path).ok()?;
+        let line = contents.lines().next()?.trim().to_string();
+        if line.is_empty() {
+            return None;
+        }
+        let id = path
+            .file_name()?
+            .to_str()?
+            .trim_end_matches("-release")
+            .to_string();
+        Some(HashMap::from([
+            ("pretty_name", line.clone()),
+            ("name", line),
+            ("id", id),
+        ]))
+    })
+}
+
+/// Fills in the placeholders of `format`. Missing fields are left empty and
+/// the doubled spaces they leave behind are collapsed.
+fn format_fields(format: &str, fields: &HashMap<&'static str, String>) -> String {
+    let mut text = format.replace("{arch}", std::env::consts::ARCH);
+    for placeholder in [
+        "pretty_name",
+        "name",
+        "version",
+        "version_id",
+        "id",
+        "codename",
+    ] {
+        let value = fields.get(placeholder).map(String::as_str).unwrap_or("");
+        text = text.replace(&format!("{{{}}}", placeholder), value);
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
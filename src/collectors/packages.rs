@@ -0,0 +1,205 @@
+use super::{Collector, Registry};
+use crate::system_info::{is_termux, InfoValue, PackageCount};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Arc;
+
+/// Counts one manager's packages, or returns `None` when it isn't installed.
+type Counter = fn() -> Option<usize>;
+
+pub fn register(registry: &mut Registry) {
+    registry.register(Arc::new(Packages));
+}
+
+/// Counts installed packages for every package manager present, reading
+/// their databases directly where the format allows it.
+struct Packages;
+
+impl Collector for Packages {
+    fn key(&self) -> &'static str {
+        "Packages"
+    }
+
+    fn collect(&self) -> Option<InfoValue> {
+        let counters: &[(&str, Counter)] = &[
+            ("pacman", pacman),
+            (if is_termux() { "pkg" } else { "dpkg" }, dpkg),
+            ("rpm", rpm),
+            ("apk", apk),
+            ("xbps", xbps),
+            ("flatpak", flatpak),
+            ("snap", snap),
+            ("nix-system", || {
+                nix_profile(Path::new("/run/current-system/sw"))
+            }),
+            ("nix-default", || {
+                nix_profile(Path::new("/nix/var/nix/profiles/default"))
+            }),
+            ("nix-user", || {
+                nix_profile(&dirs::home_dir()?.join(".nix-profile"))
+            }),
+            ("cargo", cargo),
+        ];
+
+        let packages: Vec<PackageCount> = counters
+            .iter()
+            .filter_map(|(manager, count)| {
+                let count = count().filter(|count| *count > 0)?;
+                Some(PackageCount {
+                    manager: manager.to_string(),
+                    count,
+                })
+            })
+            .collect();
+        if packages.is_empty() {
+            None
+        } else {
+            Some(InfoValue::Packages(packages))
+        }
+    }
+}
+
+/// The number of directories in `path`.
+fn count_dirs(path: &Path) -> Option<usize> {
+    let entries = fs::read_dir(path).ok()?;
+    Some(
+        entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_dir()))
+            .count(),
+    )
+}
+
+/// One directory per package in /var/lib/pacman/local.
+fn pacman() -> Option<usize> {
+    count_dirs(Path::new("/var/lib/pacman/local"))
+}
+
+/// Entries of the dpkg status file that are fully installed. Termux's `pkg`
+/// is dpkg with its database under $PREFIX.
+fn dpkg() -> Option<usize> {
+    let status = match std::env::var("PREFIX") {
+        Ok(prefix) if is_termux() => Path::new(&prefix).join("var/lib/dpkg/status"),
+        _ => PathBuf::from("/var/lib/dpkg/status"),
+    };
+    let contents = fs::read_to_string(status).ok()?;
+    Some(
+        contents
+            .lines()
+            .filter(|line| *line == "Status: install ok installed")
+            .count(),
+    )
+}
+
+/// The rpm database is SQLite or Berkeley DB, so this asks rpm itself.
+fn rpm() -> Option<usize> {
+    if !Path::new("/var/lib/rpm").is_dir() && !Path::new("/usr/lib/sysimage/rpm").is_dir() {
+        return None;
+    }
+    count_output(Command::new("rpm").arg("-qa"))
+}
+
+/// Every package in /lib/apk/db/installed starts with a `P:` line.
+fn apk() -> Option<usize> {
+    let contents = fs::read_to_string("/lib/apk/db/installed").ok()?;
+    Some(
+        contents
+            .lines()
+            .filter(|line| line.starts_with("P:"))
+            .count(),
+    )
+}
+
+/// Every package in the xbps pkgdb plist has a `pkgver` key.
+fn xbps() -> Option<usize> {
+    let pkgdb = fs::read_dir("/var/db/xbps")
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .find(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("pkgdb-") && name.ends_with(".plist"))
+        })?;
+    let contents = fs::read_to_string(pkgdb).ok()?;
+    Some(contents.matches("<key>pkgver</key>").count())
+}
+
+/// Apps and runtimes of the system and user installations, counting each
+/// installed `name/arch/branch` once.
+fn flatpak() -> Option<usize> {
+    let mut installations = vec![PathBuf::from("/var/lib/flatpak")];
+    installations.extend(dirs::data_dir().map(|data| data.join("flatpak")));
+
+    let mut found = false;
+    let mut count = 0;
+    for installation in installations {
+        for kind in ["app", "runtime"] {
+            let Ok(refs) = fs::read_dir(installation.join(kind)) else {
+                continue;
+            };
+            found = true;
+            for name in refs.filter_map(|entry| entry.ok()) {
+                let Ok(arches) = fs::read_dir(name.path()) else {
+                    continue;
+                };
+                // Skips the `current` symlink next to the arch directories
+                count += arches
+                    .filter_map(|entry| entry.ok())
+                    .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_dir()))
+                    .filter_map(|entry| count_dirs(&entry.path()))
+                    .sum::<usize>();
+            }
+        }
+    }
+    found.then_some(count)
+}
+
+/// One directory per snap in /snap, besides the `bin` directory.
+fn snap() -> Option<usize> {
+    let entries = fs::read_dir("/snap").ok()?;
+    Some(
+        entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_dir()))
+            .filter(|entry| entry.file_name() != "bin")
+            .count(),
+    )
+}
+
+/// Store paths a nix profile depends on, as neofetch counts them.
+fn nix_profile(profile: &Path) -> Option<usize> {
+    if !profile.exists() {
+        return None;
+    }
+    count_output(
+        Command::new("nix-store")
+            .arg("--query")
+            .arg("--requisites")
+            .arg(profile),
+    )
+}
+
+/// Crates installed with `cargo install`, listed in `$CARGO_HOME/.crates.toml`.
+fn cargo() -> Option<usize> {
+    let cargo_home = std::env::var_os("CARGO_HOME")
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(".cargo")))?;
+    let contents = fs::read_to_string(cargo_home.join(".crates.toml")).ok()?;
+    let crates: toml::Value = contents.parse().ok()?;
+    Some(crates.get("v1")?.as_table()?.len())
+}
+
+/// The number of lines a successful command prints.
+fn count_output(command: &mut Command) -> Option<usize> {
+    let output = command.output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .count(),
+    )
+}
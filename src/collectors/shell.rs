@@ -0,0 +1,209 @@
+use super::{Collector, Registry};
+use crate::system_info::InfoValue;
+use regex::Regex;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Arc;
+
+const SHELLS: &[&str] = &[
+    "bash", "zsh", "fish", "sh", "dash", "ksh", "mksh", "oksh", "loksh", "tcsh", "csh", "yash",
+    "nu", "elvish", "xonsh", "ion", "pwsh", "osh", "ysh",
+];
+
+/// Processes that sit between a shell and its terminal without being either.
+const WRAPPERS: &[&str] = &[
+    "sudo", "su", "doas", "login", "script", "strace", "ltrace", "gdb", "valgrind", "time",
+    "watch", "nohup", "env", "cargo", "make", "just", "piko",
+];
+
+/// Terminal emulators and multiplexers by process name, with display names.
+const TERMINALS: &[(&str, &str)] = &[
+    ("alacritty", "Alacritty"),
+    ("kitty", "kitty"),
+    ("wezterm-gui", "WezTerm"),
+    ("wezterm", "WezTerm"),
+    ("foot", "foot"),
+    ("footclient", "foot"),
+    ("gnome-terminal", "GNOME Terminal"),
+    ("kgx", "GNOME Console"),
+    ("konsole", "Konsole"),
+    ("xfce4-terminal", "Xfce Terminal"),
+    ("mate-terminal", "MATE Terminal"),
+    ("lxterminal", "LXTerminal"),
+    ("qterminal", "QTerminal"),
+    ("terminator", "Terminator"),
+    ("tilix", "Tilix"),
+    ("guake", "Guake"),
+    ("yakuake", "Yakuake"),
+    ("st", "st"),
+    ("urxvt", "urxvt"),
+    ("rxvt", "rxvt"),
+    ("xterm", "xterm"),
+    ("ghostty", "Ghostty"),
+    ("rio", "Rio"),
+    ("contour", "Contour"),
+    ("blackbox", "Black Box"),
+    ("terminology", "Terminology"),
+    ("code", "VS Code"),
+    ("Terminal", "Terminal.app"),
+    ("iTerm2", "iTerm2"),
+    ("tmux", "tmux"),
+    ("screen", "screen"),
+    ("zellij", "zellij"),
+    ("sshd", "SSH"),
+];
+
+pub fn register(registry: &mut Registry) {
+    registry.register(Arc::new(Shell));
+    registry.register(Arc::new(Terminal));
+}
+
+/// The shell piko was started from, with its version.
+struct Shell;
+
+impl Collector for Shell {
+    fn key(&self) -> &'static str {
+        "Shell"
+    }
+
+    fn collect(&self) -> Option<InfoValue> {
+        let (name, exe) = match ancestors().into_iter().find(|p| is_shell(&p.name)) {
+            Some(process) => (process.name, process.exe),
+            None => {
+                // Not started from a shell, e.g. from a launcher
+                let shell = PathBuf::from(std::env::var_os("SHELL")?);
+                let name = shell.file_name()?.to_string_lossy().into_owned();
+                (name, Some(shell))
+            }
+        };
+
+        let version = exe.and_then(|exe| shell_version(&name, &exe));
+        Some(InfoValue::Text(match version {
+            Some(version) => format!("{} {}", name, version),
+            None => name,
+        }))
+    }
+}
+
+/// The terminal emulator, found by walking up past the shell. Falls back to
+/// `$TERM_PROGRAM`, then `$TERM`. Without any of them, as under cron, there is
+/// no terminal to report.
+struct Terminal;
+
+impl Collector for Terminal {
+    fn key(&self) -> &'static str {
+        "Terminal"
+    }
+
+    fn collect(&self) -> Option<InfoValue> {
+        if let Ok(version) = std::env::var("TERMUX_VERSION") {
+            return Some(InfoValue::Text(format!("Termux {}", version)));
+        }
+
+        // Start above the shell, or at piko's parent when there is none
+        let ancestors = ancestors();
+        let start = ancestors
+            .iter()
+            .position(|process| is_shell(&process.name))
+            .map_or(0, |shell| shell + 1);
+        let candidates: Vec<&Process> = ancestors[start..]
+            .iter()
+            .filter(|process| {
+                !is_shell(&process.name) && !WRAPPERS.contains(&process.name.as_str())
+            })
+            .take_while(|process| process.pid > 1)
+            .collect();
+
+        let known = candidates.iter().find_map(|process| {
+            TERMINALS
+                .iter()
+                .find(|(name, _)| is_name_match(&process.name, name))
+                .map(|(_, display)| display.to_string())
+        });
+        let terminal = known
+            .or_else(|| {
+                let program = std::env::var("TERM_PROGRAM").ok()?;
+                Some(match std::env::var("TERM_PROGRAM_VERSION") {
+                    Ok(version) => format!("{} {}", program, version),
+                    Err(_) => program,
+                })
+            })
+            .or_else(|| std::env::var("TERM").ok().filter(|term| term != "dumb"))?;
+        Some(InfoValue::Text(terminal))
+    }
+}
+
+struct Process {
+    pid: u32,
+    name: String,
+    exe: Option<PathBuf>,
+}
+
+/// piko's ancestors from /proc, parent first, up to and including init.
+fn ancestors() -> Vec<Process> {
+    let mut ancestors = Vec::new();
+    let mut pid = parent_pid("self");
+    while let Some(current) = pid.filter(|pid| *pid > 0) {
+        let Some(name) = process_name(current) else {
+            break;
+        };
+        ancestors.push(Process {
+            pid: current,
+            name,
+            exe: fs::read_link(format!("/proc/{}/exe", current)).ok(),
+        });
+        // Guard against a pid being reused into a loop
+        if ancestors.len() > 64 {
+            break;
+        }
+        pid = parent_pid(&current.to_string());
+    }
+    ancestors
+}
+
+/// The ppid field of /proc/<pid>/stat. The command name before it is in
+/// parentheses and may itself contain spaces and parentheses.
+fn parent_pid(pid: &str) -> Option<u32> {
+    let stat = fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    let after_name = &stat[stat.rfind(')')? + 1..];
+    after_name.split_whitespace().nth(1)?.parse().ok()
+}
+
+/// The command name, without the leading `-` of login shells.
+fn process_name(pid: u32) -> Option<String> {
+    let comm = fs::read_to_string(format!("/proc/{}/comm", pid)).ok()?;
+    let name = comm.trim().trim_start_matches('-');
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+fn is_shell(name: &str) -> bool {
+    SHELLS.contains(&name)
+}
+
+/// Whether `process` is `name`, or `name` followed by a separator as in
+/// "tmux: server" or "gnome-terminal-" (the kernel cuts command names to 15
+/// bytes, so that is all that is left of "gnome-terminal-server").
+fn is_name_match(process: &str, name: &str) -> bool {
+    let Some(rest) = process.strip_prefix(name) else {
+        return false;
+    };
+    rest.is_empty() || rest.starts_with([':', ' ', '-', '.'])
+}
+
+/// Asks the shell binary for its version. Shells without a version flag,
+/// such as dash, report nothing.
+fn shell_version(name: &str, exe: &Path) -> Option<String> {
+    if matches!(
+        name,
+        "sh" | "dash" | "ksh" | "mksh" | "oksh" | "loksh" | "csh"
+    ) {
+        return None;
+    }
+    let output = Command::new(exe).arg("--version").output().ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let version = Regex::new(r"\d+(\.\d+)+").expect("valid regex");
+    version
+        .find(text.lines().next()?)
+        .map(|found| found.as_str().to_string())
+}
@@ -0,0 +1,22 @@
+use super::{Collector, Registry};
+use crate::system_info::InfoValue;
+use std::sync::Arc;
+use std::time::Duration;
+use sysinfo::{System, SystemExt};
+
+pub fn register(registry: &mut Registry) {
+    registry.register(Arc::new(Uptime));
+}
+
+struct Uptime;
+
+impl Collector for Uptime {
+    fn key(&self) -> &'static str {
+        "Uptime"
+    }
+
+    fn collect(&self) -> Option<InfoValue> {
+        let system = System::new();
+        Some(InfoValue::Uptime(Duration::from_secs(system.uptime())))
+    }
+}
@@ -0,0 +1,447 @@
+// A minimal Wayland client, just enough to ask the compositor about its
+// outputs. Messages are a header (object id, then the size in bytes in the
+// upper and the opcode in the lower 16 bits) followed by 32-bit arguments;
+// strings carry their length including the NUL and are padded to 4 bytes.
+
+use crate::system_info::DisplayInfo;
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::time::Duration;
+
+const WL_DISPLAY: u32 = 1;
+const HEADER_LEN: usize = 8;
+
+/// wl_output.mode flag of the mode in use.
+const MODE_CURRENT: u32 = 0x1;
+
+/// The current mode and scale of every output the compositor advertises.
+/// The scale is the mode's width over the logical width xdg-output reports,
+/// which also covers fractional scaling; without xdg-output the integer
+/// wl_output scale is used.
+pub fn outputs() -> Option<Vec<DisplayInfo>> {
+    let display = PathBuf::from(std::env::var_os("WAYLAND_DISPLAY")?);
+    let path = if display.is_absolute() {
+        display
+    } else {
+        PathBuf::from(std::env::var_os("XDG_RUNTIME_DIR")?).join(display)
+    };
+    let stream = UnixStream::connect(path).ok()?;
+    stream
+        .set_read_timeout(Some(Duration::from_millis(500)))
+        .ok()?;
+    let mut connection = Connection {
+        stream,
+        pending: Vec::new(),
+        next_id: WL_DISPLAY + 1,
+    };
+
+    // wl_display.get_registry, then wait for the list of globals
+    let registry = connection.new_id();
+    connection.request(WL_DISPLAY, 1, &Args::new().uint(registry))?;
+    let mut globals = Vec::new();
+    for event in connection.roundtrip()? {
+        if event.object == registry && event.opcode == 0 {
+            let mut args = event.args();
+            globals.push((args.uint()?, args.string()?, args.uint()?));
+        }
+    }
+
+    let mut outputs = Vec::new();
+    for (name, interface, version) in &globals {
+        if interface == "wl_output" {
+            let id = connection.bind(registry, *name, interface, (*version).min(4))?;
+            outputs.push(Output::new(id));
+        }
+    }
+    if let Some((name, interface, version)) = globals
+        .iter()
+        .find(|(_, interface, _)| interface == "zxdg_output_manager_v1")
+    {
+        let manager = connection.bind(registry, *name, interface, (*version).min(3))?;
+        for output in &mut outputs {
+            let xdg_output = connection.new_id();
+            // zxdg_output_manager_v1.get_xdg_output
+            connection.request(manager, 1, &Args::new().uint(xdg_output).uint(output.id))?;
+            output.xdg_id = Some(xdg_output);
+        }
+    }
+
+    for event in connection.roundtrip()? {
+        if let Some(output) = outputs.iter_mut().find(|output| output.id == event.object) {
+            output.apply_output_event(&event);
+        } else if let Some(output) = outputs
+            .iter_mut()
+            .find(|output| output.xdg_id == Some(event.object))
+        {
+            output.apply_xdg_event(&event);
+        }
+    }
+
+    Some(
+        outputs
+            .iter()
+            .enumerate()
+            .filter_map(|(index, output)| output.display(index))
+            .collect(),
+    )
+}
+
+struct Connection {
+    stream: UnixStream,
+    pending: Vec<u8>,
+    next_id: u32,
+}
+
+impl Connection {
+    fn new_id(&mut self) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    fn request(&mut self, object: u32, opcode: u16, args: &Args) -> Option<()> {
+        let size = (HEADER_LEN + args.0.len()) as u32;
+        let mut message = Vec::with_capacity(size as usize);
+        message.extend_from_slice(&object.to_ne_bytes());
+        message.extend_from_slice(&((size << 16) | opcode as u32).to_ne_bytes());
+        message.extend_from_slice(&args.0);
+        self.stream.write_all(&message).ok()
+    }
+
+    /// wl_registry.bind, whose new id is preceded by the interface and version.
+    fn bind(&mut self, registry: u32, name: u32, interface: &str, version: u32) -> Option<u32> {
+        let id = self.new_id();
+        let args = Args::new()
+            .uint(name)
+            .string(interface)
+            .uint(version)
+            .uint(id);
+        self.request(registry, 0, &args)?;
+        Some(id)
+    }
+
+    /// Sends wl_display.sync and returns every event received before its
+    /// callback is done, which is everything the earlier requests caused.
+    fn roundtrip(&mut self) -> Option<Vec<Event>> {
+        let callback = self.new_id();
+        self.request(WL_DISPLAY, 0, &Args::new().uint(callback))?;
+
+        let mut events = Vec::new();
+        let mut buffer = [0u8; 4096];
+        loop {
+            let (messages, consumed) = split_messages(&self.pending);
+            self.pending.drain(..consumed);
+            for event in messages {
+                match (event.object, event.opcode) {
+                    (object, 0) if object == callback => return Some(events),
+                    // wl_display.error
+                    (WL_DISPLAY, 0) => return None,
+                    _ => events.push(event),
+                }
+            }
+            let read = self.stream.read(&mut buffer).ok()?;
+            if read == 0 {
+                return None;
+            }
+            self.pending.extend_from_slice(&buffer[..read]);
+        }
+    }
+}
+
+/// Encodes request arguments.
+struct Args(Vec<u8>);
+
+impl Args {
+    fn new() -> Self {
+        Args(Vec::new())
+    }
+
+    fn uint(mut self, value: u32) -> Self {
+        self.0.extend_from_slice(&value.to_ne_bytes());
+        self
+    }
+
+    fn string(mut self, value: &str) -> Self {
+        self = self.uint(value.len() as u32 + 1);
+        self.0.extend_from_slice(value.as_bytes());
+        self.0.push(0);
+        while !self.0.len().is_multiple_of(4) {
+            self.0.push(0);
+        }
+        self
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Event {
+    object: u32,
+    opcode: u16,
+    payload: Vec<u8>,
+}
+
+impl Event {
+    fn args(&self) -> ArgReader<'_> {
+        ArgReader(&self.payload)
+    }
+}
+
+/// Decodes event arguments in order.
+struct ArgReader<'a>(&'a [u8]);
+
+impl ArgReader<'_> {
+    fn uint(&mut self) -> Option<u32> {
+        let value = u32::from_ne_bytes(self.0.get(..4)?.try_into().ok()?);
+        self.0 = &self.0[4..];
+        Some(value)
+    }
+
+    fn int(&mut self) -> Option<i32> {
+        self.uint().map(|value| value as i32)
+    }
+
+    fn string(&mut self) -> Option<String> {
+        let len = self.uint()? as usize;
+        let padded = (len + 3) & !3;
+        let bytes = self.0.get(..padded)?;
+        self.0 = &self.0[padded..];
+        let text = bytes.get(..len.saturating_sub(1))?;
+        Some(String::from_utf8_lossy(text).into_owned())
+    }
+}
+
+/// The complete messages at the start of `data`, and how many bytes they take.
+fn split_messages(data: &[u8]) -> (Vec<Event>, usize) {
+    let mut events = Vec::new();
+    let mut offset = 0;
+    while data.len() - offset >= HEADER_LEN {
+        let word = |at: usize| u32::from_ne_bytes(data[at..at + 4].try_into().unwrap());
+        let object = word(offset);
+        let size = (word(offset + 4) >> 16) as usize;
+        let opcode = (word(offset + 4) & 0xffff) as u16;
+        if size < HEADER_LEN || data.len() - offset < size {
+            break;
+        }
+        events.push(Event {
+            object,
+            opcode,
+            payload: data[offset + HEADER_LEN..offset + size].to_vec(),
+        });
+        offset += size;
+    }
+    (events, offset)
+}
+
+/// What the compositor told about one wl_output and its xdg_output.
+#[derive(Default)]
+struct Output {
+    id: u32,
+    xdg_id: Option<u32>,
+    name: Option<String>,
+    /// Width, height and refresh rate in mHz of the current mode.
+    mode: Option<(i32, i32, i32)>,
+    transform: i32,
+    scale: Option<i32>,
+    logical_size: Option<(i32, i32)>,
+}
+
+impl Output {
+    fn new(id: u32) -> Self {
+        Output {
+            id,
+            ..Default::default()
+        }
+    }
+
+    fn apply_output_event(&mut self, event: &Event) -> Option<()> {
+        let mut args = event.args();
+        match event.opcode {
+            // geometry: x, y, physical size, subpixel, make, model, transform
+            0 => {
+                for _ in 0..5 {
+                    args.int()?;
+                }
+                args.string()?;
+                args.string()?;
+                self.transform = args.int()?;
+            }
+            // mode: flags, width, height, refresh
+            1 => {
+                let flags = args.uint()?;
+                let mode = (args.int()?, args.int()?, args.int()?);
+                if flags & MODE_CURRENT != 0 {
+                    self.mode = Some(mode);
+                }
+            }
+            3 => self.scale = Some(args.int()?),
+            4 => self.name = Some(args.string()?),
+            _ => {}
+        }
+        Some(())
+    }
+
+    fn apply_xdg_event(&mut self, event: &Event) -> Option<()> {
+        let mut args = event.args();
+        match event.opcode {
+            1 => self.logical_size = Some((args.int()?, args.int()?)),
+            // Only wl_output v4 has a name of its own
+            3 if self.name.is_none() => self.name = Some(args.string()?),
+            _ => {}
+        }
+        Some(())
+    }
+
+    fn display(&self, index: usize) -> Option<DisplayInfo> {
+        let (width, height, refresh) = self.mode?;
+        // Transforms 1, 3, 5 and 7 turn the output by 90 or 270 degrees
+        let logical_width = self.logical_size.map(|(logical_width, logical_height)| {
+            if self.transform % 2 == 1 {
+                logical_height
+            } else {
+                logical_width
+            }
+        });
+        let scale = match logical_width.filter(|width| *width > 0) {
+            Some(logical) => Some((width as f64 / logical as f64 * 100.0).round() / 100.0),
+            None => self.scale.map(f64::from),
+        };
+        Some(DisplayInfo {
+            name: self
+                .name
+                .clone()
+                .unwrap_or_else(|| format!("output-{}", index)),
+            width: u32::try_from(width).ok()?,
+            height: u32::try_from(height).ok()?,
+            refresh_hz: (refresh > 0).then(|| refresh as f64 / 1000.0),
+            scale,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(object: u32, opcode: u16, args: Args) -> Vec<u8> {
+        let size = (HEADER_LEN + args.0.len()) as u32;
+        let mut message = object.to_ne_bytes().to_vec();
+        message.extend_from_slice(&((size << 16) | opcode as u32).to_ne_bytes());
+        message.extend_from_slice(&args.0);
+        message
+    }
+
+    fn event(object: u32, opcode: u16, args: Args) -> Event {
+        Event {
+            object,
+            opcode,
+            payload: args.0,
+        }
+    }
+
+    #[test]
+    fn complete_messages_are_split_off() {
+        let mut data = message(2, 0, Args::new().uint(7).string("wl_output").uint(4));
+        data.extend(message(3, 2, Args::new()));
+        let complete = data.len();
+        data.extend(&message(4, 1, Args::new().uint(1))[..10]);
+
+        let (events, consumed) = split_messages(&data);
+        assert_eq!(consumed, complete);
+        assert_eq!(
+            events,
+            [
+                event(2, 0, Args::new().uint(7).string("wl_output").uint(4)),
+                event(3, 2, Args::new()),
+            ]
+        );
+        let mut args = events[0].args();
+        assert_eq!(args.uint(), Some(7));
+        assert_eq!(args.string().as_deref(), Some("wl_output"));
+        assert_eq!(args.uint(), Some(4));
+        assert_eq!(args.uint(), None);
+    }
+
+    #[test]
+    fn strings_are_padded_to_four_bytes() {
+        assert_eq!(
+            Args::new().string("abc").0,
+            [4, 0, 0, 0, b'a', b'b', b'c', 0]
+        );
+        assert_eq!(Args::new().string("abcd").0.len(), 12);
+    }
+
+    fn mode(flags: u32, width: u32, height: u32, refresh: u32) -> Event {
+        event(
+            1,
+            1,
+            Args::new()
+                .uint(flags)
+                .uint(width)
+                .uint(height)
+                .uint(refresh),
+        )
+    }
+
+    #[test]
+    fn fractional_scale_comes_from_the_logical_size() {
+        let mut output = Output::new(1);
+        output.apply_output_event(&mode(0, 1920, 1080, 60000));
+        output.apply_output_event(&mode(MODE_CURRENT, 2880, 1800, 120000));
+        output.apply_output_event(&event(1, 3, Args::new().uint(2)));
+        output.apply_output_event(&event(1, 4, Args::new().string("eDP-1")));
+        output.apply_xdg_event(&event(2, 1, Args::new().uint(1920).uint(1200)));
+
+        assert_eq!(
+            output.display(0),
+            Some(DisplayInfo {
+                name: "eDP-1".to_string(),
+                width: 2880,
+                height: 1800,
+                refresh_hz: Some(120.0),
+                scale: Some(1.5),
+            })
+        );
+    }
+
+    #[test]
+    fn rotated_outputs_compare_against_the_logical_height() {
+        let mut output = Output::new(1);
+        let geometry = Args::new()
+            .uint(0)
+            .uint(0)
+            .uint(600)
+            .uint(340)
+            .uint(0)
+            .string("Dell")
+            .string("U2720Q")
+            .uint(1);
+        output.apply_output_event(&event(1, 0, geometry));
+        output.apply_output_event(&mode(MODE_CURRENT, 3840, 2160, 0));
+        output.apply_xdg_event(&event(2, 1, Args::new().uint(1080).uint(1920)));
+        output.apply_xdg_event(&event(2, 3, Args::new().string("DP-1")));
+
+        let display = output.display(0).unwrap();
+        assert_eq!(display.name, "DP-1");
+        assert_eq!(display.scale, Some(2.0));
+        assert_eq!(display.refresh_hz, None);
+    }
+
+    #[test]
+    fn integer_scale_without_xdg_output() {
+        let mut output = Output::new(1);
+        output.apply_output_event(&mode(MODE_CURRENT, 1920, 1080, 59940));
+        output.apply_output_event(&event(1, 3, Args::new().uint(1)));
+
+        let display = output.display(2).unwrap();
+        assert_eq!(display.name, "output-2");
+        assert_eq!(display.scale, Some(1.0));
+        assert_eq!(display.refresh_hz, Some(59.94));
+    }
+
+    #[test]
+    fn outputs_without_a_current_mode_are_skipped() {
+        let mut output = Output::new(1);
+        output.apply_output_event(&mode(0, 1920, 1080, 60000));
+        assert_eq!(output.display(0), None);
+    }
+}
@@ -1,10 +1,25 @@
 // This file handles reading and parsing the configuration file.
 // It exports a struct Config that holds the customizable layout settings for the output.
+//
+// Configuration is layered. Each layer is deep-merged over the previous one, so
+// a later file only needs the keys it changes:
+//
+//   0. config/default_config.toml, embedded in the binary
+//   1. /etc/piko/default_config.toml (system)
+//   2. $XDG_CONFIG_HOME/piko/config.toml (user)
+//   3. the file passed with --config
+//   4. command line flags
 
+use crate::system_info::is_termux;
 use serde::Deserialize;
 use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
 use toml::Value;
 
+/// The lowest configuration layer, so piko runs without any files installed.
+const BUILTIN_CONFIG: &str = include_str!("../config/default_config.toml");
+
 #[allow(dead_code)]
 #[derive(Debug, Deserialize)]
 pub struct Config {
@@ -15,6 +30,8 @@ pub struct Config {
     pub logo: LogoConfig,
     pub theme: ThemeConfig,
     pub display: DisplayConfig,
+    #[serde(default)]
+    pub modules: ModulesConfig,
 }
 
 #[allow(dead_code)]
@@ -33,6 +50,7 @@ pub struct OutputConfig {
 pub struct LogoConfig {
     pub enabled: Option<bool>,
     pub distro: Option<String>, // "auto", "arch", "ubuntu", etc.
+    pub file: Option<String>,   // plain art, or a .toml logo
     pub custom_ascii: Option<Vec<String>>,
     pub custom_colors: Option<Vec<String>>,
     pub padding: Option<usize>,
@@ -66,6 +84,28 @@ pub struct DisplayConfig {
     pub separator_style: Option<String>, // "dash", "equals", "dots", "none"
 }
 
+#[allow(dead_code)]
+#[derive(Debug, Default, Deserialize)]
+pub struct ModulesConfig {
+    pub timeout_ms: Option<u64>, // applies to every module without its own timeout
+    #[serde(flatten)]
+    pub per_module: HashMap<String, ModuleConfig>, // keyed by info key, e.g. [modules."GPU Model"]
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Default, Deserialize)]
+pub struct ModuleConfig {
+    pub timeout_ms: Option<u64>,
+    pub format: Option<String>, // for modules with a format, e.g. [modules.OS]
+    pub show_loopback: Option<bool>, // interface filters of [modules.Network]
+    pub show_docker: Option<bool>,
+    pub show_veth: Option<bool>,
+    pub hide_mac: Option<bool>,
+    pub interval_ms: Option<u64>, // sampling interval of [modules."CPU Usage"]
+    pub warning: Option<f64>,     // thresholds that color the value
+    pub critical: Option<f64>,
+}
+
 impl Config {
     pub fn from_value(value: &Value) -> Result<Self, Box<dyn std::error::Error>> {
         let config: Config = value.clone().try_into()?;
@@ -126,12 +166,95 @@ impl Config {
     }
 }
 
+/// The system-wide configuration file installed with piko.
+pub fn system_config_path() -> PathBuf {
+    if is_termux() {
+        // On Termux, use the user's home directory
+        dirs::home_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join(".config")
+            .join("piko")
+            .join("default_config.toml")
+    } else if cfg!(target_os = "macos") {
+        dirs::config_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("piko")
+            .join("default_config.toml")
+    } else {
+        PathBuf::from("/etc/piko/default_config.toml")
+    }
+}
+
+/// The per-user configuration file, `$XDG_CONFIG_HOME/piko/config.toml`.
+pub fn user_config_path() -> Option<PathBuf> {
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| dirs::home_dir().map(|home| home.join(".config")))?;
+    Some(config_home.join("piko").join("config.toml"))
+}
+
+/// The configuration files that apply, lowest precedence first. The system
+/// and user files are skipped when missing; a `custom` path is always kept.
+pub fn layer_paths(custom: Option<&Path>) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = [Some(system_config_path()), user_config_path()]
+        .into_iter()
+        .flatten()
+        .filter(|path| path.is_file())
+        .collect();
+    if let Some(custom) = custom {
+        paths.push(custom.to_path_buf());
+    }
+    paths.dedup();
+    paths
+}
+
+/// The configuration embedded in the binary.
+pub fn builtin_config() -> Value {
+    BUILTIN_CONFIG
+        .parse()
+        .expect("the built-in configuration is valid TOML")
+}
+
+/// Reads every configuration layer and merges them over the built-in defaults.
+pub fn load_layered(custom: Option<&Path>) -> Result<Value, Box<dyn std::error::Error>> {
+    let mut config = builtin_config();
+    for path in layer_paths(custom) {
+        let contents = fs::read_to_string(&path)
+            .map_err(|e| format!("unable to read {}: {}", path.display(), e))?;
+        let layer: Value = contents
+            .parse()
+            .map_err(|e| format!("invalid TOML in {}: {}", path.display(), e))?;
+        merge(&mut config, layer);
+    }
+    Ok(config)
+}
+
+/// Deep-merges `overlay` into `base`. Tables are merged key by key; any other
+/// value, arrays included, replaces the one in `base`.
+pub fn merge(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Table(base), Value::Table(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
 // Default implementations
 impl Default for LogoConfig {
     fn default() -> Self {
         LogoConfig {
             enabled: Some(true),
             distro: Some("auto".to_string()),
+            file: None,
             custom_ascii: None,
             custom_colors: None,
             padding: Some(2),
@@ -170,3 +293,83 @@ impl Default for DisplayConfig {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(source: &str) -> Value {
+        source.parse().unwrap()
+    }
+
+    #[test]
+    fn nested_tables_merge_key_by_key() {
+        let mut base = parse(
+            "[display]\nborder = false\npadding = 1\n\n[modules.CPU]\nformat = \"{model}\"\ntimeout_ms = 500\n",
+        );
+        merge(
+            &mut base,
+            parse("[display]\nborder = true\n\n[modules.CPU]\ntimeout_ms = 100\n"),
+        );
+        assert_eq!(
+            base,
+            parse(
+                "[display]\nborder = true\npadding = 1\n\n[modules.CPU]\nformat = \"{model}\"\ntimeout_ms = 100\n"
+            )
+        );
+    }
+
+    #[test]
+    fn arrays_are_replaced_not_merged() {
+        let mut base = parse("[output]\ninfo_keys = [\"OS\", \"Kernel Version\", \"Uptime\"]\n");
+        merge(&mut base, parse("[output]\ninfo_keys = [\"CPU\"]\n"));
+        assert_eq!(base, parse("[output]\ninfo_keys = [\"CPU\"]\n"));
+    }
+
+    #[test]
+    fn a_colors_only_layer_keeps_everything_else() {
+        let mut config = builtin_config();
+        merge(&mut config, parse("[colors]\nOS = \"#123456\"\n"));
+
+        let builtin = builtin_config();
+        let table = config.as_table().unwrap();
+        assert_eq!(table.len(), builtin.as_table().unwrap().len());
+        for (section, value) in builtin.as_table().unwrap() {
+            if section != "colors" {
+                assert_eq!(&table[section], value, "[{}] changed", section);
+            }
+        }
+
+        let colors = table["colors"].as_table().unwrap();
+        let builtin_colors = builtin["colors"].as_table().unwrap();
+        assert_eq!(colors["OS"].as_str(), Some("#123456"));
+        assert_eq!(colors.len(), builtin_colors.len());
+        assert_eq!(colors["Username"], builtin_colors["Username"]);
+    }
+
+    #[test]
+    fn the_custom_file_is_the_last_layer_even_when_missing() {
+        let custom = Path::new("/nonexistent/piko.toml");
+        let paths = layer_paths(Some(custom));
+        assert_eq!(paths.last().map(PathBuf::as_path), Some(custom));
+        assert!(paths[..paths.len() - 1].iter().all(|path| path.is_file()));
+    }
+
+    #[test]
+    fn builtin_config_is_valid() {
+        let config = builtin_config();
+        Config::from_value(&config).unwrap();
+
+        let registry = crate::collectors::Registry::builtin(&config);
+        let info_keys: Vec<&str> = registry.keys().collect();
+        let diagnostics: Vec<String> = crate::validate::check_file(
+            Path::new("config/default_config.toml"),
+            BUILTIN_CONFIG,
+            &info_keys,
+        )
+        .iter()
+        .map(|diagnostic| diagnostic.to_string())
+        .collect();
+        assert_eq!(diagnostics, Vec::<String>::new());
+    }
+}
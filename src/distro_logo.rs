@@ -1,13 +1,16 @@
+use crate::os_release::OsRelease;
+use serde::Deserialize;
 use std::collections::HashMap;
+use std::error::Error;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use toml::Value;
 // use anyhow::Result;
 
+#[derive(Clone)]
 pub struct DistroLogo {
-    #[allow(dead_code)]
     pub name: String,
     pub ascii_art: Vec<String>,
-    #[allow(dead_code)]
     pub colors: Vec<String>,
 }
 
@@ -19,6 +22,107 @@ impl DistroLogo {
             colors,
         }
     }
+
+    /// Reads a logo from a file. A `.toml` file gives the `name`, `colors` and
+    /// `art` of the logo; any other file is taken as plain art, named after
+    /// the file.
+    pub fn from_file(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("unable to read {}: {}", path.display(), e))?;
+        let stem = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        if path.extension().is_some_and(|ext| ext == "toml") {
+            let file: LogoFile = toml::from_str(&contents)
+                .map_err(|e| format!("invalid logo file {}: {}", path.display(), e))?;
+            Ok(DistroLogo::new(
+                file.name.unwrap_or(stem),
+                art_lines(&file.art),
+                file.colors,
+            ))
+        } else {
+            Ok(DistroLogo::new(stem, art_lines(&contents), Vec::new()))
+        }
+    }
+}
+
+/// The on-disk format of a `.toml` logo.
+#[derive(Deserialize)]
+struct LogoFile {
+    name: Option<String>,
+    #[serde(default)]
+    colors: Vec<String>,
+    art: String,
+}
+
+/// Splits art into lines, dropping trailing blank lines.
+fn art_lines(art: &str) -> Vec<String> {
+    let mut lines: Vec<String> = art.lines().map(String::from).collect();
+    while lines.last().is_some_and(|line| line.trim().is_empty()) {
+        lines.pop();
+    }
+    lines
+}
+
+/// The directory user logos are loaded from, `$XDG_DATA_HOME/piko/logos`.
+pub fn user_logo_dir() -> Option<PathBuf> {
+    let data_home = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| dirs::home_dir().map(|home| home.join(".local").join("share")))?;
+    Some(data_home.join("piko").join("logos"))
+}
+
+/// Whether `line` contains any neofetch-style `${cN}` color placeholder.
+pub fn has_color_placeholders(line: &str) -> bool {
+    !split_color_placeholders(line, 0)
+        .iter()
+        .all(|(index, _)| *index == 0)
+}
+
+/// Splits a line of logo art at its `${c1}`..`${c9}` placeholders, pairing each
+/// piece of text with the 1-based color index in effect for it. `current` is
+/// the index carried over from the previous line, 0 meaning no color yet. The
+/// placeholders themselves are dropped, so they never count towards the width.
+pub fn split_color_placeholders(line: &str, current: usize) -> Vec<(usize, &str)> {
+    let mut segments = Vec::new();
+    let mut color = current;
+    let mut rest = line;
+    let mut searched = 0;
+
+    while let Some(found) = rest[searched..].find("${c") {
+        let start = searched + found;
+        let tail = &rest[start + 3..];
+        let digit = tail
+            .chars()
+            .next()
+            .and_then(|c| c.to_digit(10))
+            .filter(|d| *d >= 1);
+        match digit {
+            Some(d) if tail[1..].starts_with('}') => {
+                segments.push((color, &rest[..start]));
+                color = d as usize;
+                rest = &tail[2..];
+                searched = 0;
+            }
+            // Not a placeholder; keep it as literal text.
+            _ => searched = start + 3,
+        }
+    }
+    segments.push((color, rest));
+    segments
+}
+
+/// Logo choices made on the command line. They outrank everything in the
+/// configuration files, whatever the order of precedence among those.
+#[derive(Default)]
+pub struct LogoOverrides {
+    /// `--distro`
+    pub distro: Option<String>,
+    /// `--logo-file`
+    pub file: Option<PathBuf>,
 }
 
 pub struct LogoManager {
@@ -31,9 +135,36 @@ impl LogoManager {
             logos: HashMap::new(),
         };
         manager.load_builtin_logos();
+        manager.load_user_logos();
         manager
     }
 
+    /// Loads every `*.txt` and `*.toml` logo in the user logo directory. Each
+    /// is keyed by its `name`, or else its file name, so `arch.txt` replaces
+    /// the built-in `arch`.
+    fn load_user_logos(&mut self) {
+        let Some(entries) = user_logo_dir().and_then(|dir| fs::read_dir(dir).ok()) else {
+            return;
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|ext| ext == "txt" || ext == "toml")
+            })
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            match DistroLogo::from_file(&path) {
+                Ok(logo) => {
+                    self.logos.insert(logo.name.to_lowercase(), logo);
+                }
+                Err(e) => eprintln!("Skipping logo: {}", e),
+            }
+        }
+    }
+
     fn load_builtin_logos(&mut self) {
         // Arch Linux
         self.logos.insert(
@@ -257,52 +388,289 @@ impl LogoManager {
                     " / /  / /".to_string(),
                     "/_/  /_/".to_string(),
                 ],
-                vec![
-                    "#000000".to_string(), // Black
-                ],
+                // No palette, so it is drawn in the terminal's own foreground color
+                Vec::new(),
             ),
         );
     }
 
-    pub fn detect_distro() -> String {
-        // Try to detect the distribution
-        if let Ok(os_release) = fs::read_to_string("/etc/os-release") {
-            for line in os_release.lines() {
-                if let Some(id) = line.strip_prefix("ID=") {
-                    let id = id.trim_matches('"');
-                    return id.to_lowercase();
-                }
+    /// Names the running distribution might have a logo under, most specific
+    /// first, ending with the distributions it derives from.
+    pub fn detect_distros() -> Vec<String> {
+        if let Some(os_release) = OsRelease::load() {
+            let candidates = os_release.candidates();
+            if !candidates.is_empty() {
+                return candidates;
             }
         }
 
         // Fallback detection methods
-        if Path::new("/etc/arch-release").exists() {
-            return "arch".to_string();
+        let release_files = [
+            ("/etc/arch-release", "arch"),
+            ("/etc/debian_version", "debian"),
+            ("/etc/fedora-release", "fedora"),
+            ("/etc/redhat-release", "centos"),
+        ];
+        release_files
+            .iter()
+            .filter(|(path, _)| Path::new(path).exists())
+            .map(|(_, distro)| distro.to_string())
+            .take(1)
+            .collect()
+    }
+
+    pub fn get_logo(&self, distro: &str) -> Option<&DistroLogo> {
+        self.logos.get(distro)
+    }
+
+    /// The logo of the first detected name that has one, so derivatives such
+    /// as Pop!_OS fall back to Ubuntu and then Debian.
+    pub fn get_detected_logo(&self) -> &DistroLogo {
+        self.first_logo(&Self::detect_distros())
+    }
+
+    /// The logo of the first of `distros` that has one, or the default logo.
+    fn first_logo(&self, distros: &[String]) -> &DistroLogo {
+        distros
+            .iter()
+            .find_map(|distro| self.get_logo(distro))
+            .unwrap_or_else(|| self.get_logo("default").unwrap())
+    }
+
+    /// The logo the command line or else the configuration asks for: a logo
+    /// file, then a named distribution, then the inline `logo.custom_ascii`
+    /// art, then the detected distribution's logo.
+    pub fn select(
+        &self,
+        config: &Value,
+        overrides: &LogoOverrides,
+    ) -> Result<DistroLogo, Box<dyn Error>> {
+        if let Some(file) = &overrides.file {
+            return DistroLogo::from_file(file);
+        }
+        if let Some(distro) = &overrides.distro {
+            return self.named_or_detected(distro);
         }
-        if Path::new("/etc/debian_version").exists() {
-            return "debian".to_string();
+
+        let logo_config = config.get("logo");
+
+        if let Some(file) = logo_config
+            .and_then(|logo| logo.get("file"))
+            .and_then(|v| v.as_str())
+            .filter(|file| !file.is_empty())
+        {
+            return DistroLogo::from_file(Path::new(file));
         }
-        if Path::new("/etc/fedora-release").exists() {
-            return "fedora".to_string();
+
+        if let Some(distro) = logo_config
+            .and_then(|logo| logo.get("distro"))
+            .and_then(|v| v.as_str())
+            .filter(|distro| !distro.is_empty() && *distro != "auto")
+        {
+            return self.named_or_detected(distro);
         }
-        if Path::new("/etc/redhat-release").exists() {
-            return "centos".to_string();
+
+        let custom_ascii: Vec<String> = logo_config
+            .and_then(|logo| logo.get("custom_ascii"))
+            .and_then(|v| v.as_array())
+            .map(|lines| {
+                lines
+                    .iter()
+                    .filter_map(|line| line.as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default();
+        if !custom_ascii.is_empty() {
+            return Ok(DistroLogo::new(
+                "custom".to_string(),
+                custom_ascii,
+                Vec::new(),
+            ));
         }
 
-        "default".to_string()
+        Ok(self.get_detected_logo().clone())
     }
 
-    pub fn get_logo(&self, distro: &str) -> Option<&DistroLogo> {
-        self.logos.get(distro)
+    /// The logo named `distro`, or the detected one for "auto".
+    fn named_or_detected(&self, distro: &str) -> Result<DistroLogo, Box<dyn Error>> {
+        if distro == "auto" {
+            return Ok(self.get_detected_logo().clone());
+        }
+        self.get_named_logo(distro).cloned()
     }
 
-    pub fn get_detected_logo(&self) -> &DistroLogo {
-        let detected = Self::detect_distro();
-        self.get_logo(&detected)
-            .unwrap_or_else(|| self.get_logo("default").unwrap())
+    /// Looks up a logo by name, ignoring case. The error for an unknown name
+    /// suggests the closest available names.
+    pub fn get_named_logo(&self, name: &str) -> Result<&DistroLogo, Box<dyn Error>> {
+        let name = name.to_lowercase();
+        if let Some(logo) = self.get_logo(&name) {
+            return Ok(logo);
+        }
+
+        let mut matches: Vec<(usize, &String)> = self
+            .list_available_logos()
+            .into_iter()
+            .map(|available| (edit_distance(&name, available), available))
+            .filter(|(distance, available)| {
+                *distance <= std::cmp::max(2, name.len() / 3)
+                    || available.contains(name.as_str())
+                    || name.contains(available.as_str())
+            })
+            .collect();
+        matches.sort();
+
+        let message = if matches.is_empty() {
+            format!(
+                "unknown logo \"{}\" (run `piko --list-logos` to see the available logos)",
+                name
+            )
+        } else {
+            let names: Vec<&str> = matches.iter().map(|(_, name)| name.as_str()).collect();
+            format!(
+                "unknown logo \"{}\" (did you mean: {}?)",
+                name,
+                names.join(", ")
+            )
+        };
+        Err(message.into())
     }
 
+    /// Every logo name, sorted.
     pub fn list_available_logos(&self) -> Vec<&String> {
-        self.logos.keys().collect()
+        let mut names: Vec<&String> = self.logos.keys().collect();
+        names.sort();
+        names
+    }
+}
+
+/// The Levenshtein distance between `a` and `b`, counted in characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn placeholders_switch_colors_and_are_dropped() {
+        assert_eq!(
+            split_color_placeholders("${c1}/\\${c2}__", 0),
+            vec![(0, ""), (1, "/\\"), (2, "__")]
+        );
+    }
+
+    #[test]
+    fn color_carries_over_from_the_previous_line() {
+        assert_eq!(split_color_placeholders("  /  \\", 2), vec![(2, "  /  \\")]);
+        assert_eq!(
+            split_color_placeholders("==${c1}==", 3),
+            vec![(3, "=="), (1, "==")]
+        );
+    }
+
+    #[test]
+    fn two_digit_and_zero_indices_are_literal_text() {
+        assert_eq!(
+            split_color_placeholders("a${c10}b", 1),
+            vec![(1, "a${c10}b")]
+        );
+        assert_eq!(split_color_placeholders("${c0}b", 0), vec![(0, "${c0}b")]);
+        assert_eq!(
+            split_color_placeholders("${c0}${c3}x", 0),
+            vec![(0, "${c0}"), (3, "x")]
+        );
+        assert!(!has_color_placeholders("${c10} ${c0} ${c"));
+        assert!(has_color_placeholders("${c9}"));
+    }
+
+    /// A manager with only the built-in logos, whatever is installed.
+    fn builtin_manager() -> LogoManager {
+        let mut manager = LogoManager {
+            logos: HashMap::new(),
+        };
+        manager.load_builtin_logos();
+        manager
+    }
+
+    #[test]
+    fn derivatives_get_the_logo_of_the_distro_they_are_like() {
+        let manager = builtin_manager();
+        let logo_for = |os_release: &str| {
+            let candidates = OsRelease::parse(os_release).candidates();
+            manager.first_logo(&candidates).name.clone()
+        };
+
+        assert_eq!(logo_for("ID=pop\nID_LIKE=\"ubuntu debian\"\n"), "Ubuntu");
+        assert_eq!(
+            logo_for("ID=\"rocky\"\nID_LIKE=\"rhel centos fedora\"\n"),
+            "CentOS"
+        );
+        assert_eq!(logo_for("ID=plan9\n"), "Linux");
+    }
+
+    #[test]
+    fn edit_distance_counts_characters() {
+        assert_eq!(edit_distance("arch", "arch"), 0);
+        assert_eq!(edit_distance("arhc", "arch"), 2);
+        assert_eq!(edit_distance("fedra", "fedora"), 1);
+        assert_eq!(edit_distance("", "alpine"), 6);
+        assert_eq!(edit_distance("ärch", "arch"), 1);
+    }
+
+    #[test]
+    fn names_are_matched_without_regard_to_case() {
+        let manager = builtin_manager();
+        assert_eq!(manager.get_named_logo("Arch").unwrap().name, "Arch Linux");
+    }
+
+    #[test]
+    fn unknown_names_suggest_the_closest_logos() {
+        let manager = builtin_manager();
+        let error = |name: &str| manager.get_named_logo(name).err().unwrap().to_string();
+
+        assert_eq!(error("arhc"), "unknown logo \"arhc\" (did you mean: arch?)");
+        assert_eq!(
+            error("Ubunto"),
+            "unknown logo \"ubunto\" (did you mean: ubuntu?)"
+        );
+        // Substrings count too, however far apart
+        assert_eq!(
+            error("opensuse-tumbleweed"),
+            "unknown logo \"opensuse-tumbleweed\" (did you mean: opensuse?)"
+        );
+        assert_eq!(
+            error("windows"),
+            "unknown logo \"windows\" (run `piko --list-logos` to see the available logos)"
+        );
+    }
+
+    #[test]
+    fn command_line_outranks_the_configured_file() {
+        let manager = builtin_manager();
+        let config: Value =
+            toml::from_str("[logo]\nfile = \"/nonexistent/logo.txt\"\ndistro = \"fedora\"\n")
+                .unwrap();
+
+        let overrides = LogoOverrides {
+            distro: Some("Arch".to_string()),
+            file: None,
+        };
+        let logo = manager.select(&config, &overrides).unwrap();
+        assert_eq!(logo.name, "Arch Linux");
+
+        // Without overrides the configured file is used, and here it is missing
+        assert!(manager.select(&config, &LogoOverrides::default()).is_err());
     }
 }
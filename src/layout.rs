@@ -0,0 +1,202 @@
+// Width measurement for laying out colored text in the terminal.
+// Byte lengths are useless for alignment once lines carry truecolor escape
+// codes and emoji, so every padding and border computation goes through here.
+
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Removes ANSI escape sequences (SGR colors and other CSI sequences) from `s`.
+pub fn strip_ansi(s: &str) -> String {
+    let mut stripped = String::with_capacity(s.len());
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\u{1b}' {
+            stripped.push(c);
+            continue;
+        }
+        // CSI runs up to a final byte in @..~; any other escape is a single
+        // character long and has already been consumed.
+        if chars.next() == Some('[') {
+            for c in chars.by_ref() {
+                if ('@'..='~').contains(&c) {
+                    break;
+                }
+            }
+        }
+    }
+
+    stripped
+}
+
+/// The number of terminal columns `s` occupies, ignoring escape sequences and
+/// counting wide characters such as emoji and CJK as two columns.
+pub fn display_width(s: &str) -> usize {
+    if !s.contains('\u{1b}') {
+        return s.width();
+    }
+    strip_ansi(s).width()
+}
+
+/// The widest line in `lines`, in terminal columns.
+pub fn max_width<S: AsRef<str>>(lines: &[S]) -> usize {
+    lines
+        .iter()
+        .map(|line| display_width(line.as_ref()))
+        .max()
+        .unwrap_or(0)
+}
+
+/// Pads `s` with spaces on the right up to `width` columns.
+pub fn pad_to(s: &str, width: usize) -> String {
+    let padding = width.saturating_sub(display_width(s));
+    format!("{}{}", s, " ".repeat(padding))
+}
+
+/// Repeats `pattern` until it fills exactly `width` columns. A pattern that
+/// does not divide `width` evenly is cut short on the last repetition, and one
+/// that takes no columns at all, such as a tab, gives an empty line. Control
+/// characters are left out.
+pub fn repeat_to_width(pattern: &str, width: usize) -> String {
+    let mut line = String::new();
+    let mut used = 0;
+    'fill: loop {
+        let pass_start = used;
+        // Control characters would move the cursor and break the alignment
+        for c in pattern.chars().filter(|c| !c.is_control()) {
+            let char_width = c.width().unwrap_or(0);
+            if used + char_width > width {
+                break 'fill;
+            }
+            line.push(c);
+            used += char_width;
+        }
+        if used == pass_start {
+            line.clear();
+            break;
+        }
+    }
+    line
+}
+
+/// The characters used to draw one `display.border_style`.
+pub struct BorderStyle {
+    top_left: char,
+    top_right: char,
+    bottom_left: char,
+    bottom_right: char,
+    horizontal: char,
+    vertical: char,
+}
+
+impl BorderStyle {
+    /// Looks up a style by name. Returns `None` for "none"; unknown names fall
+    /// back to "single".
+    pub fn from_name(name: &str) -> Option<Self> {
+        let [top_left, top_right, bottom_left, bottom_right, horizontal, vertical] = match name {
+            "none" => return None,
+            "double" => ['╔', '╗', '╚', '╝', '═', '║'],
+            "rounded" => ['╭', '╮', '╰', '╯', '─', '│'],
+            "heavy" => ['┏', '┓', '┗', '┛', '━', '┃'],
+            "ascii" => ['+', '+', '+', '+', '-', '|'],
+            _ => ['┌', '┐', '└', '┘', '─', '│'],
+        };
+        Some(BorderStyle {
+            top_left,
+            top_right,
+            bottom_left,
+            bottom_right,
+            horizontal,
+            vertical,
+        })
+    }
+}
+
+/// Draws a box around `lines` with `padding` columns of space inside each side
+/// wall. The box fits the widest line, or spans `width` columns in total when
+/// that is wider. `paint` colors the border characters.
+pub fn draw_box(
+    lines: &[String],
+    style: &BorderStyle,
+    padding: usize,
+    width: Option<usize>,
+    paint: impl Fn(&str) -> String,
+) -> Vec<String> {
+    let content_width = max_width(lines);
+    let inner_width = std::cmp::max(
+        content_width + 2 * padding,
+        width.unwrap_or(0).saturating_sub(2),
+    );
+
+    let horizontal = style.horizontal.to_string().repeat(inner_width);
+    let vertical = paint(&style.vertical.to_string());
+    let inside = " ".repeat(padding);
+
+    let mut boxed = Vec::with_capacity(lines.len() + 2);
+    boxed.push(paint(&format!(
+        "{}{}{}",
+        style.top_left, horizontal, style.top_right
+    )));
+    for line in lines {
+        boxed.push(format!(
+            "{}{}{}{}",
+            vertical,
+            inside,
+            pad_to(line, inner_width - padding),
+            vertical
+        ));
+    }
+    boxed.push(paint(&format!(
+        "{}{}{}",
+        style.bottom_left, horizontal, style.bottom_right
+    )));
+    boxed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sgr_codes_take_no_columns() {
+        assert_eq!(display_width("\u{1b}[1;38;2;23;147;209mArch\u{1b}[0m"), 4);
+        assert_eq!(display_width("\u{1b}[31m\u{1b}[0m"), 0);
+        assert_eq!(display_width("plain"), 5);
+    }
+
+    #[test]
+    fn wide_characters_take_two_columns() {
+        assert_eq!(display_width("日本"), 4);
+        assert_eq!(display_width("\u{1b}[32m🐧\u{1b}[0m Linux"), 8);
+    }
+
+    #[test]
+    fn vs16_emoji_take_two_columns() {
+        // U+2764 is narrow on its own, the emoji presentation selector widens it
+        assert_eq!(display_width("\u{2764}"), 1);
+        assert_eq!(display_width("\u{2764}\u{fe0f}"), 2);
+        assert_eq!(display_width("\u{1b}[31m\u{2764}\u{fe0f}\u{1b}[0m ok"), 5);
+    }
+
+    #[test]
+    fn patterns_repeat_to_the_exact_width() {
+        assert_eq!(repeat_to_width("-", 4), "----");
+        assert_eq!(repeat_to_width("-=", 5), "-=-=-");
+        assert_eq!(repeat_to_width("日", 5), "日日");
+    }
+
+    #[test]
+    fn zero_width_patterns_give_an_empty_line() {
+        assert_eq!(repeat_to_width("", 10), "");
+        assert_eq!(repeat_to_width("\t", 10), "");
+        assert_eq!(repeat_to_width("\u{85}", 10), "");
+        assert_eq!(repeat_to_width("\u{200b}", 10), "");
+        assert_eq!(repeat_to_width("-\t", 3), "---");
+    }
+
+    #[test]
+    fn padding_accounts_for_escape_codes() {
+        let colored = "\u{1b}[34mOS\u{1b}[0m";
+        assert_eq!(display_width(&pad_to(colored, 6)), 6);
+        assert_eq!(max_width(&[colored, "Kernel"]), 6);
+    }
+}
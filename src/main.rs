@@ -1,19 +1,26 @@
+mod collectors;
 mod config;
 mod distro_logo;
+mod layout;
+mod os_release;
 mod output;
 mod system_info;
+mod validate;
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use toml::Value;
 
 /// A highly customizable system information tool inspired by Neofetch
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 struct Cli {
-    /// Path to a custom config file
-    #[arg(long)]
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Path to a config file, applied over the system and user configs
+    #[arg(long, global = true)]
     config: Option<PathBuf>,
 
     /// Show only the distribution logo
@@ -28,17 +35,25 @@ struct Cli {
     #[arg(long, default_value = "normal")]
     format: String,
 
-    /// Show logo position: left, right, top, bottom
-    #[arg(long, default_value = "left")]
-    logo_position: String,
+    /// Show logo position: left, right, top, bottom [default: left]
+    #[arg(long)]
+    logo_position: Option<String>,
 
-    /// Logo size: small, medium, large
-    #[arg(long, default_value = "medium")]
-    logo_size: String,
+    /// Logo size: small, medium, large [default: medium]
+    #[arg(long)]
+    logo_size: Option<String>,
 
-    /// Logo style: ascii, unicode, minimal
-    #[arg(long, default_value = "ascii")]
-    logo_style: String,
+    /// Logo style: ascii, unicode, minimal [default: ascii]
+    #[arg(long)]
+    logo_style: Option<String>,
+
+    /// Show the logo of this distribution instead of the detected one
+    #[arg(long)]
+    distro: Option<String>,
+
+    /// Draw the logo from a file: plain art, or a .toml logo with name, colors and art
+    #[arg(long)]
+    logo_file: Option<PathBuf>,
 
     /// Show border around output
     #[arg(long)]
@@ -57,88 +72,58 @@ struct Cli {
     import_config: Option<PathBuf>,
 }
 
-fn is_termux() -> bool {
-    std::env::var("TERMUX_VERSION").is_ok() || 
-    std::env::var("PREFIX").map(|p| p.contains("/data/data/com.termux")).unwrap_or(false)
+#[derive(Subcommand)]
+enum Command {
+    /// Inspect the configuration
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
 }
 
-fn get_default_config_path() -> PathBuf {
-    if is_termux() {
-        // On Termux, use the user's home directory
-        dirs::home_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join(".config")
-            .join("piko")
-            .join("default_config.toml")
-    } else if cfg!(target_os = "macos") {
-        dirs::config_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("piko")
-            .join("default_config.toml")
-    } else {
-        PathBuf::from("/etc/piko/default_config.toml")
-    }
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// Check every configuration file for unknown keys and invalid values
+    Check,
 }
 
-fn ensure_default_config_exists() {
-    let config_path = get_default_config_path();
-
-    if !config_path.exists() {
-        eprintln!("Configuration file not found at {}", config_path.display());
-        
-        if is_termux() {
-            eprintln!("On Termux, please ensure the config directory exists:");
-            eprintln!("  mkdir -p ~/.config/piko");
-            eprintln!("  cp config/default_config.toml ~/.config/piko/");
-        } else {
-            eprintln!("Please ensure Piko is properly installed.");
-        }
-        
-        std::process::exit(1);
+/// Settings given on the command line, merged over every configuration file.
+fn cli_overrides(cli: &Cli) -> Value {
+    let mut output = toml::value::Table::new();
+    if let Some(position) = &cli.logo_position {
+        output.insert("logo_position".to_string(), Value::String(position.clone()));
+    }
+    if let Some(size) = &cli.logo_size {
+        output.insert("logo_size".to_string(), Value::String(size.clone()));
+    }
+    if let Some(style) = &cli.logo_style {
+        output.insert("logo_style".to_string(), Value::String(style.clone()));
     }
-}
-
-fn load_config(config_path: Option<PathBuf>) -> Value {
-    let path = config_path.unwrap_or_else(get_default_config_path);
-
-    let contents = fs::read_to_string(&path)
-        .unwrap_or_else(|_| panic!("Unable to read the configuration file from {:?}", path));
-
-    contents.parse().expect("Invalid TOML configuration")
-}
 
-fn create_dynamic_config(cli: &Cli) -> Value {
-    let mut config = load_config(None);
-
-    // Override config with CLI options
-    if let Some(output) = config.get_mut("output") {
-        if let Some(output_table) = output.as_table_mut() {
-            output_table.insert(
-                "logo_position".to_string(),
-                toml::Value::String(cli.logo_position.clone()),
-            );
-            output_table.insert(
-                "logo_size".to_string(),
-                toml::Value::String(cli.logo_size.clone()),
-            );
-            output_table.insert(
-                "logo_style".to_string(),
-                toml::Value::String(cli.logo_style.clone()),
-            );
-        }
+    let mut logo = toml::value::Table::new();
+    if let Some(distro) = &cli.distro {
+        logo.insert("distro".to_string(), Value::String(distro.clone()));
+    }
+    if let Some(file) = &cli.logo_file {
+        logo.insert(
+            "file".to_string(),
+            Value::String(file.to_string_lossy().into_owned()),
+        );
     }
 
-    if let Some(display) = config.get_mut("display") {
-        if let Some(display_table) = display.as_table_mut() {
-            display_table.insert("border".to_string(), toml::Value::Boolean(cli.border));
-            display_table.insert(
-                "show_separators".to_string(),
-                toml::Value::Boolean(!cli.no_separators),
-            );
-        }
+    let mut display = toml::value::Table::new();
+    if cli.border {
+        display.insert("border".to_string(), Value::Boolean(true));
+    }
+    if cli.no_separators {
+        display.insert("show_separators".to_string(), Value::Boolean(false));
     }
 
-    config
+    let mut overrides = toml::value::Table::new();
+    overrides.insert("output".to_string(), Value::Table(output));
+    overrides.insert("logo".to_string(), Value::Table(logo));
+    overrides.insert("display".to_string(), Value::Table(display));
+    Value::Table(overrides)
 }
 
 fn export_config(config: &Value, path: &PathBuf) -> Result<(), Box<dyn std::error::Error>> {
@@ -150,29 +135,96 @@ fn export_config(config: &Value, path: &PathBuf) -> Result<(), Box<dyn std::erro
 
 fn import_config(path: &PathBuf) -> Result<Value, Box<dyn std::error::Error>> {
     let contents = fs::read_to_string(path)?;
-    let config: Value = contents.parse()?;
+    let imported: Value = contents.parse()?;
     println!("Configuration imported from: {}", path.display());
+
+    let mut config = config::builtin_config();
+    config::merge(&mut config, imported);
     Ok(config)
 }
 
+/// Checks each configuration layer and prints every problem found.
+/// Returns whether the configuration is clean.
+fn check_config(custom: Option<&Path>) -> bool {
+    let registry = collectors::Registry::builtin(&config::builtin_config());
+    let info_keys: Vec<&str> = registry.keys().collect();
+
+    let paths = config::layer_paths(custom);
+    if paths.is_empty() {
+        println!("No configuration files found, using the built-in defaults.");
+        return true;
+    }
+
+    let mut problems = 0;
+    for path in &paths {
+        let diagnostics = match fs::read_to_string(path) {
+            Ok(contents) => validate::check_file(path, &contents, &info_keys),
+            Err(e) => {
+                println!("{}: unable to read: {}", path.display(), e);
+                problems += 1;
+                continue;
+            }
+        };
+        for diagnostic in &diagnostics {
+            println!("{}", diagnostic);
+        }
+        problems += diagnostics.len();
+    }
+
+    if problems == 0 {
+        println!("Configuration OK ({} file(s) checked)", paths.len());
+    } else {
+        println!("{} problem(s) found", problems);
+    }
+    problems == 0
+}
+
+/// Exits with an error when the output could not be drawn, e.g. for a missing logo file.
+fn display_or_exit(result: Result<(), Box<dyn std::error::Error>>) {
+    if let Err(e) = result {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
+
 fn main() {
     let cli = Cli::parse();
 
     // Handle special commands first
+    if let Some(Command::Config {
+        action: ConfigAction::Check,
+    }) = &cli.command
+    {
+        let ok = check_config(cli.config.as_deref());
+        std::process::exit(if ok { 0 } else { 1 });
+    }
+
     if cli.list_logos {
         output::list_available_logos();
         return;
     }
 
+    // JSON and YAML hold exactly the info keys, without the user@host title
+    let structured = matches!(cli.format.as_str(), "json" | "yaml");
+    // A `logo.file` in a config file would otherwise outrank `--distro`
+    let logo_overrides = distro_logo::LogoOverrides {
+        distro: cli.distro.clone(),
+        file: cli.logo_file.clone(),
+    };
+
     // Handle import/export
     if let Some(import_path) = &cli.import_config {
         match import_config(import_path) {
             Ok(config) => {
-                let system_info = system_info::get_system_info();
+                let system_info = system_info::get_system_info(&config, !structured);
                 match cli.format.as_str() {
                     "json" => output::display_json(&system_info),
                     "yaml" => output::display_yaml(&system_info),
-                    _ => output::display_output(&system_info, &config),
+                    _ => display_or_exit(output::display_output(
+                        &system_info,
+                        &config,
+                        &logo_overrides,
+                    )),
                 }
                 return;
             }
@@ -183,16 +235,25 @@ fn main() {
         }
     }
 
-    // Load configuration
-    let config_value = if cli.config.is_none() && !cli.logo_only {
-        ensure_default_config_exists();
-        create_dynamic_config(&cli)
-    } else {
-        load_config(cli.config)
+    // Load configuration: files from lowest to highest precedence, then CLI flags
+    let mut config_value = match config::load_layered(cli.config.as_deref()) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Error loading configuration: {}", e);
+            std::process::exit(1);
+        }
     };
+    config::merge(&mut config_value, cli_overrides(&cli));
 
     // Handle export
     if let Some(export_path) = &cli.export_config {
+        // Loading the export should show the same logo, and a configured
+        // `logo.file` would outrank the `--distro` it now holds
+        if cli.distro.is_some() && cli.logo_file.is_none() {
+            if let Some(logo) = config_value.get_mut("logo").and_then(|v| v.as_table_mut()) {
+                logo.remove("file");
+            }
+        }
         if let Err(e) = export_config(&config_value, export_path) {
             eprintln!("Error exporting configuration: {}", e);
             std::process::exit(1);
@@ -201,22 +262,29 @@ fn main() {
     }
 
     // Parse configuration
-    let _config = config::Config::from_value(&config_value)
-        .expect("Unable to deserialize the configuration file");
+    if let Err(e) = config::Config::from_value(&config_value) {
+        eprintln!("Invalid configuration: {}", e);
+        eprintln!("Run `piko config check` for details.");
+        std::process::exit(1);
+    }
 
     // Handle logo-only mode
     if cli.logo_only {
-        output::display_logo_only(&config_value);
+        display_or_exit(output::display_logo_only(&config_value, &logo_overrides));
         return;
     }
 
     // Get system information
-    let system_info = system_info::get_system_info();
+    let system_info = system_info::get_system_info(&config_value, !structured);
 
     // Display output based on format
     match cli.format.as_str() {
         "json" => output::display_json(&system_info),
         "yaml" => output::display_yaml(&system_info),
-        _ => output::display_output(&system_info, &config_value),
+        _ => display_or_exit(output::display_output(
+            &system_info,
+            &config_value,
+            &logo_overrides,
+        )),
     }
 }
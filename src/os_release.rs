@@ -0,0 +1,186 @@
+// Parsing of the os-release file that identifies the running distribution.
+// See os-release(5): one KEY=value per line, values optionally quoted with
+// shell-style escapes.
+
+use std::collections::HashMap;
+use std::fs;
+
+/// The os-release locations, in the order os-release(5) asks readers to try.
+const PATHS: &[&str] = &["/etc/os-release", "/usr/lib/os-release"];
+
+#[derive(Debug, Default)]
+pub struct OsRelease {
+    fields: HashMap<String, String>,
+}
+
+impl OsRelease {
+    /// Reads the first os-release file that exists.
+    pub fn load() -> Option<Self> {
+        PATHS
+            .iter()
+            .find_map(|path| fs::read_to_string(path).ok())
+            .map(|contents| Self::parse(&contents))
+    }
+
+    pub fn parse(contents: &str) -> Self {
+        let fields = contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| line.split_once('='))
+            .map(|(key, value)| (key.trim().to_string(), unquote(value.trim())))
+            .collect();
+        OsRelease { fields }
+    }
+
+    /// The value of any field, e.g. `BUILD_ID`. Empty values count as unset.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields
+            .get(key)
+            .map(String::as_str)
+            .filter(|value| !value.is_empty())
+    }
+
+    pub fn id(&self) -> Option<&str> {
+        self.get("ID")
+    }
+
+    /// The distributions this one derives from, closest first.
+    pub fn id_like(&self) -> Vec<&str> {
+        self.get("ID_LIKE")
+            .map(|like| like.split_whitespace().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn variant_id(&self) -> Option<&str> {
+        self.get("VARIANT_ID")
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.get("NAME")
+    }
+
+    pub fn pretty_name(&self) -> Option<&str> {
+        self.get("PRETTY_NAME")
+    }
+
+    pub fn version_id(&self) -> Option<&str> {
+        self.get("VERSION_ID")
+    }
+
+    /// Identifiers for this distribution from most to least specific:
+    /// `ID-VARIANT_ID`, `ID`, the names, then every `ID_LIKE` entry. All are
+    /// lowercase with anything but letters, digits, `-` and `_` removed.
+    pub fn candidates(&self) -> Vec<String> {
+        let mut candidates = Vec::new();
+        if let (Some(id), Some(variant)) = (self.id(), self.variant_id()) {
+            candidates.push(format!("{}-{}", id, variant));
+        }
+        candidates.extend(self.id().map(String::from));
+        candidates.extend(self.name().map(String::from));
+        candidates.extend(
+            self.pretty_name()
+                .and_then(|name| name.split_whitespace().next())
+                .map(String::from),
+        );
+        candidates.extend(self.id_like().into_iter().map(String::from));
+
+        let mut normalized: Vec<String> = Vec::new();
+        for candidate in candidates {
+            let candidate: String = candidate
+                .to_lowercase()
+                .chars()
+                .filter(|c| c.is_alphanumeric() || *c == '-' || *c == '_')
+                .collect();
+            if !candidate.is_empty() && !normalized.contains(&candidate) {
+                normalized.push(candidate);
+            }
+        }
+        normalized
+    }
+}
+
+/// Removes the quotes around an os-release value and resolves its escapes.
+fn unquote(value: &str) -> String {
+    let quote = match value.chars().next() {
+        Some(q @ ('"' | '\'')) if value.len() >= 2 && value.ends_with(q) => q,
+        _ => return value.to_string(),
+    };
+    let inner = &value[1..value.len() - 1];
+    if quote == '\'' {
+        return inner.to_string();
+    }
+
+    let mut unquoted = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            unquoted.extend(chars.next());
+        } else {
+            unquoted.push(c);
+        }
+    }
+    unquoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_are_unquoted_and_unescaped() {
+        let os_release = OsRelease::parse(concat!(
+            "# comment\n",
+            "NAME=\"Debian GNU/Linux\"\n",
+            "PRETTY_NAME='Debian \\ \"12\"'\n",
+            "VERSION=\"12 \\\"bookworm\\\" \\$HOME \\\\o/\"\n",
+            "ID=debian\n",
+            "BUILD_ID=\"\"\n",
+        ));
+        assert_eq!(os_release.name(), Some("Debian GNU/Linux"));
+        assert_eq!(os_release.pretty_name(), Some("Debian \\ \"12\""));
+        assert_eq!(
+            os_release.get("VERSION"),
+            Some("12 \"bookworm\" $HOME \\o/")
+        );
+        assert_eq!(os_release.id(), Some("debian"));
+        assert_eq!(os_release.get("BUILD_ID"), None);
+    }
+
+    #[test]
+    fn pop_os_lists_ubuntu_before_debian() {
+        let os_release = OsRelease::parse(concat!(
+            "NAME=\"Pop!_OS\"\n",
+            "PRETTY_NAME=\"Pop!_OS 22.04 LTS\"\n",
+            "ID=pop\n",
+            "ID_LIKE=\"ubuntu debian\"\n",
+        ));
+        assert_eq!(
+            os_release.candidates(),
+            ["pop", "pop_os", "ubuntu", "debian"]
+        );
+    }
+
+    #[test]
+    fn rocky_lists_its_id_like_entries_last() {
+        let os_release = OsRelease::parse(concat!(
+            "NAME=\"Rocky Linux\"\n",
+            "PRETTY_NAME=\"Rocky Linux 9.3 (Blue Onyx)\"\n",
+            "ID=\"rocky\"\n",
+            "ID_LIKE=\"rhel centos fedora\"\n",
+        ));
+        assert_eq!(
+            os_release.candidates(),
+            ["rocky", "rockylinux", "rhel", "centos", "fedora"]
+        );
+    }
+
+    #[test]
+    fn variant_comes_first() {
+        let os_release = OsRelease::parse("ID=fedora\nVARIANT_ID=silverblue\nNAME=Fedora Linux\n");
+        assert_eq!(
+            os_release.candidates(),
+            ["fedora-silverblue", "fedora", "fedoralinux"]
+        );
+    }
+}
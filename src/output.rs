@@ -1,6 +1,11 @@
-use crate::distro_logo::{DistroLogo, LogoManager};
+use crate::distro_logo::{
+    has_color_placeholders, split_color_placeholders, DistroLogo, LogoManager, LogoOverrides,
+};
+use crate::layout;
+use crate::system_info::{InfoValue, SystemInfo};
 use colored::*;
 use std::collections::HashMap;
+use std::error::Error;
 use toml::Value;
 
 #[derive(Debug)]
@@ -8,10 +13,31 @@ struct LogoDisplayOptions {
     position: String,
     size: String,
     style: String,
-    show_border: bool,
+    alignment: String,
+    palette: Vec<(u8, u8, u8)>,
     compact: bool,
 }
 
+/// An entry of `output.info_keys` that draws a separator between two groups.
+pub const GROUP_SEPARATOR: &str = "---";
+
+/// A line of the info block, before separators are sized to the block.
+enum InfoLine {
+    Text(String),
+    Separator,
+}
+
+/// How the finished block of lines is framed on screen.
+#[derive(Debug)]
+struct FrameOptions {
+    border: bool,
+    border_style: String,
+    border_color: Option<(u8, u8, u8)>,
+    padding: usize,
+    margin: usize,
+    width: Option<usize>,
+}
+
 // Helper function to convert HEX color to RGB
 fn hex_to_rgb(hex: &str) -> Option<(u8, u8, u8)> {
     if hex.starts_with('#') && hex.len() == 7 {
@@ -36,8 +62,11 @@ impl OutputConfig {
     }
 }
 
-pub fn display_output(system_info: &HashMap<String, String>, config: &Value) {
-    let logo_manager = LogoManager::new();
+pub fn display_output(
+    system_info: &SystemInfo,
+    config: &Value,
+    logo_overrides: &LogoOverrides,
+) -> Result<(), Box<dyn Error>> {
     let show_logo = config
         .get("output")
         .and_then(|output| output.get("show_logo"))
@@ -62,6 +91,12 @@ pub fn display_output(system_info: &HashMap<String, String>, config: &Value) {
         .and_then(|v| v.as_str())
         .unwrap_or("ascii");
 
+    let logo_alignment = config
+        .get("logo")
+        .and_then(|logo| logo.get("alignment"))
+        .and_then(|v| v.as_str())
+        .unwrap_or("left");
+
     let compact_mode = config
         .get("display")
         .and_then(|display| display.get("compact"))
@@ -86,6 +121,32 @@ pub fn display_output(system_info: &HashMap<String, String>, config: &Value) {
         .and_then(|v| v.as_str())
         .unwrap_or("dash");
 
+    let display_usize = |key: &str| {
+        config
+            .get("display")
+            .and_then(|display| display.get(key))
+            .and_then(|v| v.as_integer())
+            .and_then(|v| usize::try_from(v).ok())
+    };
+
+    let frame = FrameOptions {
+        border: show_border,
+        border_style: config
+            .get("display")
+            .and_then(|display| display.get("border_style"))
+            .and_then(|v| v.as_str())
+            .unwrap_or("single")
+            .to_string(),
+        border_color: config
+            .get("display")
+            .and_then(|display| display.get("border_color"))
+            .and_then(|v| v.as_str())
+            .and_then(hex_to_rgb),
+        padding: display_usize("padding").unwrap_or(1),
+        margin: display_usize("margin").unwrap_or(0),
+        width: display_usize("width"),
+    };
+
     let info_keys = config
         .get("output")
         .and_then(|output| output.get("info_keys"))
@@ -95,7 +156,7 @@ pub fn display_output(system_info: &HashMap<String, String>, config: &Value) {
                 .filter_map(|key| key.as_str().map(String::from))
                 .collect::<Vec<String>>()
         })
-        .unwrap_or_else(|| system_info.keys().cloned().collect());
+        .unwrap_or_else(|| system_info.keys().map(String::from).collect());
 
     let dummy_map = toml::map::Map::new();
     let show_flags = config
@@ -127,14 +188,31 @@ pub fn display_output(system_info: &HashMap<String, String>, config: &Value) {
 
     // Get logo if enabled
     let logo = if show_logo {
-        Some(logo_manager.get_detected_logo())
+        Some(LogoManager::new().select(config, logo_overrides)?)
     } else {
         None
     };
 
+    let show_title = show_flags
+        .get("show_title")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(true);
+
     // Prepare system info lines
-    let mut info_lines = Vec::new();
+    let mut entries = Vec::new();
+    if show_title {
+        if let Some(title) = title_line(system_info, &colors, show_flags) {
+            entries.push(InfoLine::Text(title));
+            entries.push(InfoLine::Separator);
+        }
+    }
+
     for key in info_keys {
+        if key == GROUP_SEPARATOR {
+            entries.push(InfoLine::Separator);
+            continue;
+        }
+
         let flag_key = format!(
             "show_{}",
             key.to_lowercase()
@@ -152,6 +230,9 @@ pub fn display_output(system_info: &HashMap<String, String>, config: &Value) {
             if let Some(value) = system_info.get(&key) {
                 let color_hex = colors.get(&key).unwrap_or(&default_color);
                 let rgb_color = hex_to_rgb(color_hex).unwrap_or((255, 255, 255));
+                let value_color =
+                    threshold_color(&key, value, config, &colors).unwrap_or(rgb_color);
+                let value = value.to_string();
                 let symbol = symbols.get(&key).map(String::as_str).unwrap_or("");
 
                 // Handle multi-line values (like disk information)
@@ -162,9 +243,9 @@ pub fn display_output(system_info: &HashMap<String, String>, config: &Value) {
                         "{} {}: {}",
                         symbol,
                         key.truecolor(rgb_color.0, rgb_color.1, rgb_color.2),
-                        lines[0].truecolor(rgb_color.0, rgb_color.1, rgb_color.2)
+                        lines[0].truecolor(value_color.0, value_color.1, value_color.2)
                     );
-                    info_lines.push(line);
+                    entries.push(InfoLine::Text(line));
                 } else {
                     // Multiple lines - first line with label, others indented
                     for (i, line_content) in lines.iter().enumerate() {
@@ -174,16 +255,16 @@ pub fn display_output(system_info: &HashMap<String, String>, config: &Value) {
                                 "{} {}: {}",
                                 symbol,
                                 key.truecolor(rgb_color.0, rgb_color.1, rgb_color.2),
-                                line_content.truecolor(rgb_color.0, rgb_color.1, rgb_color.2)
+                                line_content.truecolor(value_color.0, value_color.1, value_color.2)
                             );
-                            info_lines.push(line);
+                            entries.push(InfoLine::Text(line));
                         } else {
                             // Additional lines indented
                             let line = format!(
                                 "{}",
-                                line_content.truecolor(rgb_color.0, rgb_color.1, rgb_color.2)
+                                line_content.truecolor(value_color.0, value_color.1, value_color.2)
                             );
-                            info_lines.push(line);
+                            entries.push(InfoLine::Text(line));
                         }
                     }
                 }
@@ -191,137 +272,264 @@ pub fn display_output(system_info: &HashMap<String, String>, config: &Value) {
         }
     }
 
+    let separator_pattern = match separator_style {
+        _ if !show_separators => None,
+        "none" => None,
+        "dash" => Some("-"),
+        "equals" => Some("="),
+        "dots" => Some("."),
+        custom => Some(custom),
+    };
+    let separator_color = colors.get("Separator").and_then(|hex| hex_to_rgb(hex));
+    let info_lines = resolve_separators(entries, separator_pattern, separator_color);
+
     // Display with logo
-    if let Some(distro_logo) = logo {
+    let lines = if let Some(distro_logo) = &logo {
         let options = LogoDisplayOptions {
             position: logo_position.to_string(),
             size: logo_size.to_string(),
             style: logo_style.to_string(),
-            show_border,
+            alignment: logo_alignment.to_string(),
+            palette: logo_palette(distro_logo, config),
             compact: compact_mode,
         };
-        display_with_logo(distro_logo, &info_lines, &options);
+        compose_with_logo(distro_logo, &info_lines, &options)
     } else {
-        display_simple(
-            &info_lines,
-            show_border,
-            show_separators,
-            separator_style,
-            compact_mode,
-        );
-    }
-}
+        info_lines
+    };
 
-fn display_with_logo(logo: &DistroLogo, info_lines: &[String], options: &LogoDisplayOptions) {
-    let logo_art = get_logo_art(logo, &options.size, &options.style);
-    let logo_height = logo_art.len();
-    let info_height = info_lines.len();
+    print_framed(&lines, &frame);
+    Ok(())
+}
 
-    // Find the maximum width of the logo for proper alignment
-    let logo_width = logo_art.iter().map(|line| line.len()).max().unwrap_or(0);
-    let padding = if options.compact { 2 } else { 4 };
+fn compose_with_logo(
+    logo: &DistroLogo,
+    info_lines: &[String],
+    options: &LogoDisplayOptions,
+) -> Vec<String> {
+    let logo_art = get_logo_art(logo, &options.palette, &options.size, &options.style);
 
-    if options.show_border {
-        print_border_start();
+    match options.position.as_str() {
+        "top" | "bottom" => stacked(&logo_art, info_lines, options),
+        _ => side_by_side(&logo_art, info_lines, options),
     }
+}
 
-    // Display logo and info side by side with proper alignment
-    for i in 0..std::cmp::max(logo_height, info_height) {
-        let logo_line = if i < logo_height {
-            Some(&logo_art[i])
+fn side_by_side(
+    logo_art: &[String],
+    info_lines: &[String],
+    options: &LogoDisplayOptions,
+) -> Vec<String> {
+    // Widths are measured in terminal columns, so colored text and emoji line up
+    let logo_width = layout::max_width(logo_art);
+    let info_width = layout::max_width(info_lines);
+    let gap = " ".repeat(if options.compact { 2 } else { 4 });
+
+    let mut lines = Vec::new();
+    for i in 0..std::cmp::max(logo_art.len(), info_lines.len()) {
+        let logo_line = logo_art.get(i).map(String::as_str).unwrap_or("");
+        let info_line = info_lines.get(i).map(String::as_str).unwrap_or("");
+
+        let line = if options.position == "right" {
+            format!(
+                "{}{}{}",
+                layout::pad_to(info_line, info_width),
+                gap,
+                logo_line
+            )
         } else {
-            None
+            // Default to left
+            format!(
+                "{}{}{}",
+                layout::pad_to(logo_line, logo_width),
+                gap,
+                info_line
+            )
         };
+        lines.push(line.trim_end().to_string());
+    }
+    lines
+}
 
-        let info_line = if i < info_height {
-            Some(&info_lines[i])
-        } else {
-            None
-        };
+/// Places the logo above ("top") or below ("bottom") the info block, within
+/// the width of the whole block according to `logo.alignment`.
+fn stacked(
+    logo_art: &[String],
+    info_lines: &[String],
+    options: &LogoDisplayOptions,
+) -> Vec<String> {
+    let logo_width = layout::max_width(logo_art);
+    let info_width = layout::max_width(info_lines);
+    let block_width = std::cmp::max(logo_width, info_width);
+
+    let indent = match options.alignment.as_str() {
+        "center" => (block_width - logo_width) / 2,
+        "right" => block_width - logo_width,
+        _ => 0,
+    };
 
-        match options.position.as_str() {
-            "left" => {
-                if let Some(logo_line) = logo_line {
-                    // Print logo line with proper padding to align info
-                    let current_logo_width = logo_line.len();
-                    print!("{}", logo_line);
-                    if let Some(info_line) = info_line {
-                        println!(
-                            "{}{}",
-                            " ".repeat(logo_width - current_logo_width + padding),
-                            info_line
-                        );
-                    } else {
-                        println!();
-                    }
-                } else if let Some(info_line) = info_line {
-                    // When logo is shorter, align info with the logo
-                    println!("{}{}", " ".repeat(logo_width + padding), info_line);
-                }
-            }
-            "right" => {
-                if let Some(info_line) = info_line {
-                    print!("{}", info_line);
-                    if let Some(logo_line) = logo_line {
-                        println!("{}{}", " ".repeat(padding), logo_line);
-                    } else {
-                        println!();
-                    }
-                } else if let Some(logo_line) = logo_line {
-                    let info_width = info_lines.iter().map(|line| line.len()).max().unwrap_or(0);
-                    println!("{}{}", " ".repeat(info_width + padding), logo_line);
-                }
+    let logo_lines: Vec<String> = logo_art
+        .iter()
+        .map(|line| format!("{}{}", " ".repeat(indent), line))
+        .collect();
+    let gap = if !options.compact && !logo_art.is_empty() && !info_lines.is_empty() {
+        vec![String::new()]
+    } else {
+        Vec::new()
+    };
+
+    if options.position == "bottom" {
+        [info_lines.to_vec(), gap, logo_lines].concat()
+    } else {
+        [logo_lines, gap, info_lines.to_vec()].concat()
+    }
+}
+
+/// The `Critical` or `Warning` color once a value reaches the `critical` or
+/// `warning` threshold of its `[modules."Key"]` table.
+fn threshold_color(
+    key: &str,
+    value: &InfoValue,
+    config: &Value,
+    colors: &HashMap<String, String>,
+) -> Option<(u8, u8, u8)> {
+    let metric = value.metric()?;
+    let module = config.get("modules")?.get(key)?;
+    let reached = |threshold: &str| {
+        module
+            .get(threshold)
+            .and_then(|v| v.as_float().or_else(|| v.as_integer().map(|n| n as f64)))
+            .is_some_and(|threshold| metric >= threshold)
+    };
+    let (color_key, default) = if reached("critical") {
+        ("Critical", "#FF5555")
+    } else if reached("warning") {
+        ("Warning", "#F1FA8C")
+    } else {
+        return None;
+    };
+    hex_to_rgb(colors.get(color_key).map(String::as_str).unwrap_or(default))
+}
+
+/// The `user@host` title, colored like the Username and Hostname lines. A
+/// part hidden with `show_username` or `show_hostname` is left out.
+fn title_line(
+    system_info: &SystemInfo,
+    colors: &HashMap<String, String>,
+    show_flags: &toml::map::Map<String, Value>,
+) -> Option<String> {
+    let part = |key: &str, flag: &str| {
+        let shown = show_flags
+            .get(flag)
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true);
+        shown.then(|| system_info.get(key)).flatten()
+    };
+    let username = part("Username", "show_username");
+    let hostname = part("Hostname", "show_hostname");
+
+    let paint = |text: &str, key: &str| {
+        let (r, g, b) = colors
+            .get(key)
+            .and_then(|hex| hex_to_rgb(hex))
+            .unwrap_or((255, 255, 255));
+        text.truecolor(r, g, b).bold().to_string()
+    };
+
+    match (username, hostname) {
+        (Some(username), Some(hostname)) => Some(format!(
+            "{}@{}",
+            paint(&username.to_string(), "Username"),
+            paint(&hostname.to_string(), "Hostname")
+        )),
+        (Some(username), None) => Some(paint(&username.to_string(), "Username")),
+        (None, Some(hostname)) => Some(paint(&hostname.to_string(), "Hostname")),
+        (None, None) => None,
+    }
+}
+
+/// Turns separator entries into lines as wide as the widest info line, or
+/// drops them when `pattern` is `None`. Repeated and trailing separators are
+/// collapsed so empty groups don't leave double lines behind.
+fn resolve_separators(
+    entries: Vec<InfoLine>,
+    pattern: Option<&str>,
+    color: Option<(u8, u8, u8)>,
+) -> Vec<String> {
+    let width = entries
+        .iter()
+        .filter_map(|entry| match entry {
+            InfoLine::Text(text) => Some(layout::display_width(text)),
+            InfoLine::Separator => None,
+        })
+        .max()
+        .unwrap_or(0);
+
+    let separator = pattern.map(|pattern| {
+        let line = layout::repeat_to_width(pattern, width);
+        match color {
+            Some((r, g, b)) => line.truecolor(r, g, b).to_string(),
+            None => line,
+        }
+    });
+
+    let mut lines: Vec<String> = Vec::new();
+    let mut last_was_separator = true;
+    for entry in entries {
+        match entry {
+            InfoLine::Text(text) => {
+                lines.push(text);
+                last_was_separator = false;
             }
-            _ => {
-                // Default to left
-                if let Some(logo_line) = logo_line {
-                    // Print logo line with proper padding to align info
-                    let current_logo_width = logo_line.len();
-                    print!("{}", logo_line);
-                    if let Some(info_line) = info_line {
-                        println!(
-                            "{}{}",
-                            " ".repeat(logo_width - current_logo_width + padding),
-                            info_line
-                        );
-                    } else {
-                        println!();
+            InfoLine::Separator => {
+                if let Some(separator) = &separator {
+                    if !last_was_separator {
+                        lines.push(separator.clone());
+                        last_was_separator = true;
                     }
-                } else if let Some(info_line) = info_line {
-                    // When logo is shorter, align info with the logo
-                    println!("{}{}", " ".repeat(logo_width + padding), info_line);
                 }
             }
         }
     }
-
-    if options.show_border {
-        print_border_end();
+    if last_was_separator && separator.is_some() && !lines.is_empty() {
+        lines.pop();
     }
+    lines
 }
 
-fn display_simple(
-    info_lines: &[String],
-    show_border: bool,
-    _show_separators: bool,
-    _separator_style: &str,
-    _compact: bool,
-) {
-    if show_border {
-        print_border_start();
-    }
+/// Prints `lines`, inside a border when enabled, indented by the margin.
+fn print_framed(lines: &[String], frame: &FrameOptions) {
+    let border_style = if frame.border {
+        layout::BorderStyle::from_name(&frame.border_style)
+    } else {
+        None
+    };
 
-    for line in info_lines.iter() {
-        println!("{}", line);
-    }
+    let framed = match border_style {
+        Some(style) => layout::draw_box(lines, &style, frame.padding, frame.width, |s| match frame
+            .border_color
+        {
+            Some((r, g, b)) => s.truecolor(r, g, b).to_string(),
+            None => s.to_string(),
+        }),
+        None => lines.to_vec(),
+    };
 
-    if show_border {
-        print_border_end();
+    let margin = " ".repeat(frame.margin);
+    for line in framed {
+        println!("{}{}", margin, line);
     }
 }
 
-fn get_logo_art(logo: &DistroLogo, size: &str, style: &str) -> Vec<String> {
-    let mut art = logo.ascii_art.clone();
+fn get_logo_art(
+    logo: &DistroLogo,
+    palette: &[(u8, u8, u8)],
+    size: &str,
+    style: &str,
+) -> Vec<String> {
+    // Color first, so that every line carries its own color when lines are
+    // dropped below.
+    let mut art = colorize_logo(&logo.ascii_art, palette);
 
     // Apply size modifications
     match size {
@@ -354,18 +562,92 @@ fn get_logo_art(logo: &DistroLogo, size: &str, style: &str) -> Vec<String> {
     art
 }
 
-fn print_border_start() {
-    println!("┌─────────────────────────────────────────────────────────────┐");
+/// The colors to draw `logo` with. `logo.custom_colors` wins over
+/// `output.logo_color`, which wins over the distro's own palette. Setting
+/// `logo_color = "none"` prints the logo uncolored.
+fn logo_palette(logo: &DistroLogo, config: &Value) -> Vec<(u8, u8, u8)> {
+    let custom_colors: Vec<(u8, u8, u8)> = config
+        .get("logo")
+        .and_then(|logo| logo.get("custom_colors"))
+        .and_then(|v| v.as_array())
+        .map(|colors| {
+            colors
+                .iter()
+                .filter_map(|color| color.as_str().and_then(hex_to_rgb))
+                .collect()
+        })
+        .unwrap_or_default();
+    if !custom_colors.is_empty() {
+        return custom_colors;
+    }
+
+    match config
+        .get("output")
+        .and_then(|output| output.get("logo_color"))
+        .and_then(|v| v.as_str())
+    {
+        Some("none") => Vec::new(),
+        Some(hex) if hex_to_rgb(hex).is_some() => hex_to_rgb(hex).into_iter().collect(),
+        _ => logo
+            .colors
+            .iter()
+            .filter_map(|hex| hex_to_rgb(hex))
+            .collect(),
+    }
 }
 
-fn print_border_end() {
-    println!("└─────────────────────────────────────────────────────────────┘");
+/// Paints the logo with `palette`. Art with `${c1}`..`${c9}` placeholders is
+/// colored region by region, using the last palette color for indices past its
+/// end. Otherwise, with several colors, the rows are split into that many
+/// bands from top to bottom, one color each.
+fn colorize_logo(art: &[String], palette: &[(u8, u8, u8)]) -> Vec<String> {
+    if art.iter().any(|line| has_color_placeholders(line)) {
+        // Like neofetch, a color stays in effect across lines until the next
+        // placeholder changes it.
+        let mut color = 0;
+        return art
+            .iter()
+            .map(|line| {
+                let segments = split_color_placeholders(line, color);
+                color = segments.last().map_or(color, |(index, _)| *index);
+                segments
+                    .into_iter()
+                    .map(|(index, text)| {
+                        let rgb = index
+                            .checked_sub(1)
+                            .and_then(|i| palette.get(i).or(palette.last()));
+                        match rgb {
+                            Some(&(r, g, b)) if !text.is_empty() => {
+                                text.truecolor(r, g, b).to_string()
+                            }
+                            _ => text.to_string(),
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+    }
+
+    if palette.is_empty() {
+        return art.to_vec();
+    }
+
+    let rows_per_color = art.len().div_ceil(palette.len()).max(1);
+    art.iter()
+        .enumerate()
+        .map(|(row, line)| {
+            let (r, g, b) = palette[(row / rows_per_color).min(palette.len() - 1)];
+            line.truecolor(r, g, b).to_string()
+        })
+        .collect()
 }
 
 // New function to display logo only
-pub fn display_logo_only(config: &Value) {
-    let logo_manager = LogoManager::new();
-    let logo = logo_manager.get_detected_logo();
+pub fn display_logo_only(
+    config: &Value,
+    logo_overrides: &LogoOverrides,
+) -> Result<(), Box<dyn Error>> {
+    let logo = LogoManager::new().select(config, logo_overrides)?;
 
     let size = config
         .get("output")
@@ -379,11 +661,12 @@ pub fn display_logo_only(config: &Value) {
         .and_then(|v| v.as_str())
         .unwrap_or("ascii");
 
-    let logo_art = get_logo_art(logo, size, style);
+    let logo_art = get_logo_art(&logo, &logo_palette(&logo, config), size, style);
 
     for line in logo_art {
         println!("{}", line);
     }
+    Ok(())
 }
 
 // New function to list available logos
@@ -398,14 +681,14 @@ pub fn list_available_logos() {
 }
 
 // New function to display system info in JSON format
-pub fn display_json(system_info: &HashMap<String, String>) {
+pub fn display_json(system_info: &SystemInfo) {
     use serde_json;
     let json = serde_json::to_string_pretty(system_info).unwrap();
     println!("{}", json);
 }
 
 // New function to display system info in YAML format
-pub fn display_yaml(system_info: &HashMap<String, String>) {
+pub fn display_yaml(system_info: &SystemInfo) {
     use serde_yaml;
     let yaml = serde_yaml::to_string(system_info).unwrap();
     println!("{}", yaml);
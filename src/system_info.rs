@@ -1,320 +1,419 @@
 // src/system_info.rs
 
-use std::collections::HashMap;
-use std::fs;
-use std::process::Command;
-use sysinfo::{DiskExt, System, SystemExt};
+use crate::collectors::{Registry, Timeouts};
+use serde::{Serialize, Serializer};
+use std::fmt;
+use std::net::IpAddr;
+use std::time::Duration;
+use toml::Value;
 
-#[allow(dead_code)]
-pub struct SystemInfo {
-    pub os: String,
-    pub cpu: String,
-    pub memory: String,
-    pub disk: String,
+/// Memory usage in bytes.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct MemoryInfo {
+    pub used_bytes: u64,
+    pub total_bytes: u64,
 }
 
-impl SystemInfo {
-    fn is_termux() -> bool {
-        std::env::var("TERMUX_VERSION").is_ok() || 
-        std::env::var("PREFIX").map(|p| p.contains("/data/data/com.termux")).unwrap_or(false)
+impl MemoryInfo {
+    pub fn used_percent(&self) -> f64 {
+        percent(self.used_bytes, self.total_bytes)
     }
+}
 
-    fn is_android() -> bool {
-        std::env::var("ANDROID_DATA").is_ok() || 
-        std::env::var("ANDROID_ROOT").is_ok() ||
-        std::path::Path::new("/system").exists()
-    }
+/// A single mounted partition.
+#[derive(Debug, Clone, Serialize)]
+pub struct DiskInfo {
+    pub mount_point: String,
+    pub file_system: String,
+    pub total_bytes: u64,
+    pub available_bytes: u64,
+}
 
-    fn get_os_info() -> String {
-        if Self::is_termux() {
-            "Android (Termux)".to_string()
-        } else if Self::is_android() {
-            "Android".to_string()
-        } else if cfg!(target_os = "linux") {
-            Command::new("uname")
-                .arg("-o")
-                .output()
-                .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
-                .unwrap_or_else(|_| "Unknown OS".to_string())
-        } else if cfg!(target_os = "macos") {
-            Command::new("sw_vers")
-                .arg("-productName")
-                .output()
-                .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
-                .unwrap_or_else(|_| "Unknown OS".to_string())
-        } else {
-            "Unsupported OS".to_string()
-        }
+impl DiskInfo {
+    pub fn used_bytes(&self) -> u64 {
+        self.total_bytes.saturating_sub(self.available_bytes)
     }
 
-    fn get_cpu_info() -> String {
-        if Self::is_termux() || Self::is_android() {
-            // On Android/Termux, try to get CPU info from /proc/cpuinfo
-            fs::read_to_string("/proc/cpuinfo")
-                .ok()
-                .and_then(|content| {
-                    content
-                        .lines()
-                        .find(|line| line.starts_with("Hardware"))
-                        .map(|line| line.split(':').nth(1).unwrap_or("").trim().to_string())
-                })
-                .unwrap_or_else(|| "ARM CPU".to_string())
-        } else {
-            Command::new("lscpu")
-                .output()
-                .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
-                .unwrap_or_else(|_| "Unknown CPU".to_string())
-        }
+    pub fn used_percent(&self) -> f64 {
+        percent(self.used_bytes(), self.total_bytes)
     }
+}
+
+/// A battery's charge and state. Times are in seconds.
+#[derive(Debug, Clone, Serialize)]
+pub struct BatteryInfo {
+    pub name: String,
+    pub capacity_percent: u64,
+    pub status: String,
+    pub ac_connected: bool,
+    pub time_to_empty: Option<u64>,
+    pub time_to_full: Option<u64>,
+    /// Full capacity against the design capacity.
+    pub health_percent: Option<u64>,
+    /// The condition Android reports instead, such as "Good".
+    pub condition: Option<String>,
+}
+
+/// A network interface that is up, with its addresses.
+#[derive(Debug, Clone, Serialize)]
+pub struct InterfaceInfo {
+    pub name: String,
+    pub addrs: Vec<InterfaceAddr>,
+    pub mac: Option<String>,
+    pub speed_mbps: Option<u64>,
+    pub ssid: Option<String>,
+}
+
+/// An address and its prefix length, as in 192.168.1.20/24.
+#[derive(Debug, Clone, Serialize)]
+pub struct InterfaceAddr {
+    pub addr: IpAddr,
+    pub prefix: u8,
+}
+
+/// A graphics card, with its kind when that can be told.
+#[derive(Debug, Clone, Serialize)]
+pub struct GpuInfo {
+    pub name: String,
+    pub kind: Option<GpuKind>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GpuKind {
+    Integrated,
+    Discrete,
+}
 
-    fn get_disk_info() -> String {
-        let mut system = System::new_all();
-        system.refresh_disks_list();
-
-        let mut disk_info = String::new();
-        let mut physical_disks = Vec::new();
-
-        for disk in system.disks() {
-            let mount_point = disk.mount_point().to_string_lossy();
-            let file_system = String::from_utf8_lossy(disk.file_system());
-            
-            // Filter out Android-specific partitions and subvolumes
-            if Self::is_android() || Self::is_termux() {
-                // Skip Android system partitions, subvolumes, and temporary mounts
-                if mount_point.contains("/system") || 
-                   mount_point.contains("/vendor") || 
-                   mount_point.contains("/product") ||
-                   mount_point.contains("/data") ||
-                   mount_point.contains("/cache") ||
-                   mount_point.contains("/mnt") ||
-                   mount_point.contains("/storage") ||
-                   mount_point.contains("/dev/block") ||
-                   mount_point.contains("tmpfs") ||
-                   mount_point.contains("proc") ||
-                   mount_point.contains("sysfs") ||
-                   mount_point.contains("devpts") ||
-                   mount_point.contains("debugfs") ||
-                   mount_point.contains("configfs") ||
-                   mount_point.contains("selinuxfs") ||
-                   mount_point.contains("cgroup") ||
-                   mount_point.contains("pstore") ||
-                   mount_point.contains("efivarfs") ||
-                   mount_point.contains("fuse") ||
-                   mount_point.contains("sdcardfs") ||
-                   mount_point.contains("f2fs") ||
-                   mount_point.contains("ext4") && mount_point.contains("/dev/block") {
-                    continue;
+/// One connected monitor and its current mode.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DisplayInfo {
+    pub name: String,
+    pub width: u32,
+    pub height: u32,
+    pub refresh_hz: Option<f64>,
+    pub scale: Option<f64>,
+}
+
+/// The number of packages one package manager has installed.
+#[derive(Debug, Clone, Serialize)]
+pub struct PackageCount {
+    pub manager: String,
+    pub count: usize,
+}
+
+/// Load averages over 1, 5 and 15 minutes.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct LoadAverage {
+    pub one: f64,
+    pub five: f64,
+    pub fifteen: f64,
+}
+
+/// Process counts from /proc.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct ProcessCount {
+    pub total: usize,
+    pub running: usize,
+}
+
+/// The value reported for one info key.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum InfoValue {
+    Text(String),
+    Memory(MemoryInfo),
+    Disks(Vec<DiskInfo>),
+    Packages(Vec<PackageCount>),
+    Gpus(Vec<GpuInfo>),
+    Batteries(Vec<BatteryInfo>),
+    Interfaces(Vec<InterfaceInfo>),
+    Displays(Vec<DisplayInfo>),
+    LoadAverage(LoadAverage),
+    Processes(ProcessCount),
+    /// Percent of total CPU time, across all cores.
+    CpuUsage(f64),
+    /// The collector did not finish in time. Serialized as `null` so that
+    /// typed values keep their type or are absent.
+    TimedOut,
+    /// Serialized as whole seconds.
+    Uptime(#[serde(serialize_with = "serialize_secs")] Duration),
+}
+
+impl fmt::Display for InfoValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InfoValue::Text(text) => write!(f, "{}", text),
+            InfoValue::Memory(memory) => write!(
+                f,
+                "{:.2} GiB / {:.2} GiB ({:.0}%)",
+                to_gib(memory.used_bytes),
+                to_gib(memory.total_bytes),
+                memory.used_percent()
+            ),
+            InfoValue::Disks(disks) => {
+                if disks.is_empty() {
+                    if is_termux() || is_android() {
+                        return write!(
+                            f,
+                            "No accessible disk partitions found (Android restrictions)"
+                        );
+                    }
+                    return write!(f, "No disks found");
                 }
-            } else {
-                // On regular Linux, filter out subvolumes and temporary filesystems
-                if mount_point.contains("@") || 
-                   mount_point.contains("tmpfs") ||
-                   mount_point.contains("proc") ||
-                   mount_point.contains("sysfs") ||
-                   mount_point.contains("devpts") ||
-                   mount_point.contains("debugfs") ||
-                   mount_point.contains("configfs") ||
-                   mount_point.contains("selinuxfs") ||
-                   mount_point.contains("cgroup") ||
-                   mount_point.contains("pstore") ||
-                   mount_point.contains("efivarfs") ||
-                   mount_point.contains("fuse") ||
-                   mount_point.contains("squashfs") ||
-                   mount_point.contains("overlay") {
-                    continue;
+                let lines: Vec<String> = disks
+                    .iter()
+                    .map(|disk| {
+                        format!(
+                            "Disk ({}): {:.2} GiB / {:.2} GiB ({:.0}%) - {}",
+                            disk.mount_point,
+                            to_gib(disk.used_bytes()),
+                            to_gib(disk.total_bytes),
+                            disk.used_percent(),
+                            disk.file_system
+                        )
+                    })
+                    .collect();
+                write!(f, "{}", lines.join("\n"))
+            }
+            InfoValue::Packages(packages) => {
+                let counts: Vec<String> = packages
+                    .iter()
+                    .map(|package| format!("{} ({})", package.count, package.manager))
+                    .collect();
+                write!(f, "{}", counts.join(", "))
+            }
+            InfoValue::Gpus(gpus) => {
+                if gpus.is_empty() {
+                    return write!(f, "Unknown GPU Model");
                 }
+                let lines: Vec<String> = gpus
+                    .iter()
+                    .map(|gpu| match gpu.kind {
+                        Some(GpuKind::Integrated) => format!("{} (integrated)", gpu.name),
+                        Some(GpuKind::Discrete) => format!("{} (discrete)", gpu.name),
+                        None => gpu.name.clone(),
+                    })
+                    .collect();
+                write!(f, "{}", lines.join("\n"))
             }
+            InfoValue::Batteries(batteries) => {
+                let lines: Vec<String> = batteries
+                    .iter()
+                    .map(|battery| {
+                        let mut state = vec![battery.status.clone()];
+                        if let Some(seconds) = battery.time_to_empty {
+                            state.push(format!("{} remaining", format_hours(seconds)));
+                        }
+                        if let Some(seconds) = battery.time_to_full {
+                            state.push(format!("{} until full", format_hours(seconds)));
+                        }
+                        if battery.ac_connected && battery.status != "Charging" {
+                            state.push("AC connected".to_string());
+                        }
 
-            // Only show actual disk partitions with meaningful space
-            let total_space = disk.total_space() as f64 / 1024.0 / 1024.0 / 1024.0; // Convert to GiB
-            if total_space > 0.1 { // Only show partitions with more than 100MB
-                let available_space = disk.available_space() as f64 / 1024.0 / 1024.0 / 1024.0; // Convert to GiB
-                let used_space = total_space - available_space;
-                let used_percentage = (used_space / total_space) * 100.0;
-
-                physical_disks.push((mount_point, total_space, used_space, used_percentage, file_system));
+                        let mut line =
+                            format!("{}% ({})", battery.capacity_percent, state.join(", "));
+                        if let Some(health) = battery.health_percent {
+                            line += &format!(", {}% health", health);
+                        } else if let Some(condition) = &battery.condition {
+                            line += &format!(", health {}", condition);
+                        }
+                        if batteries.len() > 1 {
+                            line = format!("{}: {}", battery.name, line);
+                        }
+                        line
+                    })
+                    .collect();
+                write!(f, "{}", lines.join("\n"))
             }
-        }
-
-        // Sort by mount point for consistent output
-        physical_disks.sort_by(|a, b| a.0.cmp(&b.0));
+            InfoValue::Interfaces(interfaces) => {
+                // "wlan0: 192.168.1.20/24, 2001:db8::20/64 (SSID home, 00:11:22:33:44:55, 866 Mb/s)"
+                let lines: Vec<String> = interfaces
+                    .iter()
+                    .map(|interface| {
+                        let addrs: Vec<String> = interface
+                            .addrs
+                            .iter()
+                            .map(|address| format!("{}/{}", address.addr, address.prefix))
+                            .collect();
+                        let mut details = Vec::new();
+                        details
+                            .extend(interface.ssid.as_ref().map(|ssid| format!("SSID {}", ssid)));
+                        details.extend(interface.mac.clone());
+                        details.extend(interface.speed_mbps.map(|mbps| {
+                            if mbps >= 1000 && mbps % 1000 == 0 {
+                                format!("{} Gb/s", mbps / 1000)
+                            } else {
+                                format!("{} Mb/s", mbps)
+                            }
+                        }));
 
-        for (mount_point, total_space, used_space, used_percentage, file_system) in physical_disks {
-            disk_info.push_str(&format!(
-                "Disk ({}): {:.2} GiB / {:.2} GiB ({:.0}%) - {}\n",
-                mount_point, used_space, total_space, used_percentage, file_system
-            ));
-        }
+                        let mut line = format!("{}: {}", interface.name, addrs.join(", "));
+                        if !details.is_empty() {
+                            line += &format!(" ({})", details.join(", "));
+                        }
+                        line
+                    })
+                    .collect();
+                write!(f, "{}", lines.join("\n"))
+            }
+            InfoValue::Displays(displays) => {
+                let modes: Vec<String> = displays
+                    .iter()
+                    .map(|display| {
+                        let mut mode = format!("{}x{}", display.width, display.height);
+                        if let Some(refresh) = display.refresh_hz {
+                            mode += &format!(" @ {:.0}Hz", refresh);
+                        }
+                        if let Some(scale) = display.scale.filter(|scale| *scale != 1.0) {
+                            mode += &format!(" ({}x)", scale);
+                        }
+                        mode
+                    })
+                    .collect();
+                write!(f, "{}", modes.join(", "))
+            }
+            InfoValue::LoadAverage(load) => {
+                write!(f, "{:.2}, {:.2}, {:.2}", load.one, load.five, load.fifteen)
+            }
+            InfoValue::Processes(processes) => {
+                write!(f, "{} ({} running)", processes.total, processes.running)
+            }
+            InfoValue::CpuUsage(usage) => write!(f, "{:.0}%", usage),
+            InfoValue::TimedOut => write!(f, "(timed out)"),
+            InfoValue::Uptime(uptime) => {
+                let uptime_seconds = uptime.as_secs();
+                let hours = uptime_seconds / 3600;
+                let minutes = (uptime_seconds % 3600) / 60;
+                let days = hours / 24;
 
-        if disk_info.is_empty() {
-            if Self::is_termux() || Self::is_android() {
-                "No accessible disk partitions found (Android restrictions)".to_string()
-            } else {
-                "No disks found".to_string()
+                if days > 0 {
+                    write!(f, "{}d {:02}h {:02}m", days, hours % 24, minutes)
+                } else {
+                    write!(f, "{:02}h {:02}m", hours, minutes)
+                }
             }
-        } else {
-            disk_info.trim_end().to_string() // Remove trailing newline
         }
     }
+}
 
-    fn get_cpu_model() -> String {
-        if Self::is_termux() || Self::is_android() {
-            // On Android/Termux, try different CPU info fields
-            fs::read_to_string("/proc/cpuinfo")
-                .ok()
-                .and_then(|content| {
-                    // Try multiple possible fields for Android
-                    content
-                        .lines()
-                        .find(|line| line.starts_with("Hardware"))
-                        .or_else(|| content.lines().find(|line| line.starts_with("model name")))
-                        .or_else(|| content.lines().find(|line| line.starts_with("Processor")))
-                        .map(|line| line.split(':').nth(1).unwrap_or("").trim().to_string())
-                })
-                .unwrap_or_else(|| "ARM Processor".to_string())
-        } else {
-            fs::read_to_string("/proc/cpuinfo")
-                .ok()
-                .and_then(|content| {
-                    content
-                        .lines()
-                        .find(|line| line.starts_with("model name"))
-                        .map(|line| line.split(':').nth(1).unwrap_or("").trim().to_string())
-                })
-                .unwrap_or_else(|| "Unknown CPU Model".to_string())
+impl InfoValue {
+    /// The number that `warning` and `critical` thresholds are compared
+    /// against, for values that have one.
+    pub fn metric(&self) -> Option<f64> {
+        match self {
+            InfoValue::LoadAverage(load) => Some(load.one),
+            InfoValue::Processes(processes) => Some(processes.total as f64),
+            InfoValue::CpuUsage(usage) => Some(*usage),
+            _ => None,
         }
     }
+}
 
-    pub fn get_uptime_pretty(system: &System) -> String {
-        let uptime_seconds = system.uptime();
-        let hours = uptime_seconds / 3600;
-        let minutes = (uptime_seconds % 3600) / 60;
-        let days = hours / 24;
+fn serialize_secs<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u64(duration.as_secs())
+}
 
-        if days > 0 {
-            format!("{}d {:02}h {:02}m", days, hours % 24, minutes)
-        } else {
-            format!("{:02}h {:02}m", hours, minutes)
-        }
+/// Formats a duration as "2h 13m".
+fn format_hours(seconds: u64) -> String {
+    format!("{}h {:02}m", seconds / 3600, (seconds % 3600) / 60)
+}
+
+fn to_gib(bytes: u64) -> f64 {
+    bytes as f64 / f64::powf(1024.0, 3.0)
+}
+
+fn percent(part: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 / total as f64 * 100.0
     }
+}
 
-    fn get_gpu_model() -> String {
-        if Self::is_termux() || Self::is_android() {
-            // On Android, try to get GPU info from different sources
-            if let Ok(output) = Command::new("dumpsys")
-                .arg("SurfaceFlinger")
-                .output() {
-                if let Ok(content) = String::from_utf8(output.stdout) {
-                    if let Some(line) = content.lines().find(|line| line.contains("GLES:")) {
-                        return line.split("GLES:").nth(1).unwrap_or("Unknown GPU").trim().to_string();
-                    }
-                }
-            }
-            
-            // Fallback to reading from /proc
-            if let Ok(content) = fs::read_to_string("/proc/gpuinfo") {
-                if let Some(line) = content.lines().find(|line| line.contains("GPU")) {
-                    return line.split(':').nth(1).unwrap_or("Unknown GPU").trim().to_string();
-                }
-            }
-            
-            "ARM Mali GPU".to_string()
-        } else {
-            Command::new("lspci")
-                .arg("-nn")
-                .output()
-                .ok()
-                .and_then(|output| {
-                    String::from_utf8_lossy(&output.stdout)
-                        .lines()
-                        .find(|line| line.contains("VGA compatible controller"))
-                        .map(|line| {
-                            line.split_once(": ")
-                                .map(|x| x.1)
-                                .unwrap_or("Unknown GPU Model")
-                                .trim()
-                                .to_string()
-                        })
-                })
-                .unwrap_or_else(|| "Unknown GPU Model".to_string())
+/// Collected system information, keyed by info key in collection order.
+#[derive(Debug, Default)]
+pub struct SystemInfo {
+    entries: Vec<(String, InfoValue)>,
+}
+
+impl SystemInfo {
+    pub fn insert(&mut self, key: &str, value: InfoValue) {
+        match self.entries.iter_mut().find(|(k, _)| k == key) {
+            Some(entry) => entry.1 = value,
+            None => self.entries.push((key.to_string(), value)),
         }
     }
 
-    fn get_kernel_version() -> String {
-        Command::new("uname")
-            .arg("-r")
-            .output()
-            .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
-            .unwrap_or_else(|_| "Unknown Kernel Version".to_string())
+    pub fn get(&self, key: &str) -> Option<&InfoValue> {
+        self.entries
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value)
     }
 
-    fn get_display_server() -> String {
-        if Self::is_termux() || Self::is_android() {
-            "Android SurfaceFlinger".to_string()
-        } else if std::env::var("WAYLAND_DISPLAY").is_ok() {
-            "Wayland".to_string()
-        } else if std::env::var("DISPLAY").is_ok() {
-            "Xorg".to_string()
-        } else {
-            "Unknown Display Server".to_string()
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|(key, _)| key.as_str())
+    }
+}
+
+impl Serialize for SystemInfo {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+
+        let mut map = serializer.serialize_map(Some(self.entries.len()))?;
+        for (key, value) in &self.entries {
+            map.serialize_entry(key, value)?;
         }
+        map.end()
     }
 }
 
-pub fn get_system_info() -> HashMap<String, String> {
-    let mut info = HashMap::new();
-    let mut system = System::new_all();
-    system.refresh_all();
-
-    info.insert("Username".to_string(), whoami::username().to_string());
-    info.insert(
-        "Hostname".to_string(),
-        whoami::fallible::hostname().unwrap_or_else(|_| "Unknown".to_string()),
-    );
-
-    // Detect environment
-    let is_termux = SystemInfo::is_termux();
-    let is_android = SystemInfo::is_android();
-    
-    if is_termux {
-        info.insert("Environment".to_string(), "Termux (Android)".to_string());
-    } else if is_android {
-        info.insert("Environment".to_string(), "Android".to_string());
-    } else {
-        info.insert(
-            "Desktop Environment".to_string(),
-            std::env::var("XDG_CURRENT_DESKTOP")
-                .or_else(|_| std::env::var("DESKTOP_SESSION"))
-                .unwrap_or_else(|_| "Unknown".to_string()),
-        );
+pub fn is_termux() -> bool {
+    std::env::var("TERMUX_VERSION").is_ok()
+        || std::env::var("PREFIX")
+            .map(|p| p.contains("/data/data/com.termux"))
+            .unwrap_or(false)
+}
+
+pub fn is_android() -> bool {
+    std::env::var("ANDROID_DATA").is_ok()
+        || std::env::var("ANDROID_ROOT").is_ok()
+        || std::path::Path::new("/system").exists()
+}
+
+/// Collects the keys listed in `output.info_keys`, or every built-in key when
+/// the list is missing, honoring the timeouts in `[modules]`. With `title`,
+/// the Username and Hostname the `user@host` title needs are collected too.
+pub fn get_system_info(config: &Value, title: bool) -> SystemInfo {
+    let registry = Registry::builtin(config);
+    let mut keys = config
+        .get("output")
+        .and_then(|output| output.get("info_keys"))
+        .and_then(|keys| keys.as_array())
+        .map(|keys| {
+            keys.iter()
+                .filter_map(|key| key.as_str().map(String::from))
+                .collect::<Vec<String>>()
+        })
+        .unwrap_or_else(|| registry.keys().map(String::from).collect());
+
+    // The user@host title needs these even when they aren't listed
+    let output_flag = |flag: &str| {
+        config
+            .get("output")
+            .and_then(|output| output.get(flag))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true)
+    };
+    if title && output_flag("show_title") {
+        for (key, flag) in [("Username", "show_username"), ("Hostname", "show_hostname")] {
+            if output_flag(flag) && !keys.iter().any(|k| k == key) {
+                keys.push(key.to_string());
+            }
+        }
     }
 
-    let used_memory_gib = system.used_memory() as f64 / f64::powf(1024.0, 3.0);
-    let total_memory_gib = system.total_memory() as f64 / f64::powf(1024.0, 3.0);
-    let memory_percentage = (used_memory_gib / total_memory_gib) * 100.0;
-
-    info.insert(
-        "Memory".to_string(),
-        format!(
-            "{:.2} GiB / {:.2} GiB ({:.0}%)",
-            used_memory_gib, total_memory_gib, memory_percentage
-        ),
-    );
-
-    info.insert("CPU".to_string(), SystemInfo::get_cpu_info());
-    info.insert("CPU Model".to_string(), SystemInfo::get_cpu_model());
-    info.insert("GPU Model".to_string(), SystemInfo::get_gpu_model());
-    info.insert("OS".to_string(), SystemInfo::get_os_info());
-    info.insert(
-        "Kernel Version".to_string(),
-        SystemInfo::get_kernel_version(),
-    );
-    info.insert(
-        "Display Server".to_string(),
-        SystemInfo::get_display_server(),
-    );
-    info.insert("Disk".to_string(), SystemInfo::get_disk_info());
-    info.insert("Uptime".to_string(), SystemInfo::get_uptime_pretty(&system));
-
-    info
+    registry.collect(&keys, &Timeouts::from_config(config))
 }
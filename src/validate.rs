@@ -0,0 +1,510 @@
+// Configuration checking for `piko config check`.
+// Every file is checked on its own against the known schema, and problems are
+// reported with the file, line and column they come from.
+
+use crate::layout;
+use crate::output::GROUP_SEPARATOR;
+use std::fmt;
+use std::path::Path;
+use toml::value::Table;
+use toml::Value;
+
+pub struct Diagnostic {
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}: {}",
+            self.file, self.line, self.column, self.message
+        )
+    }
+}
+
+/// What a configuration value is expected to look like.
+enum Kind {
+    Str,
+    Bool,
+    Integer,
+    /// An integer or a float.
+    Number,
+    OneOf(&'static [&'static str]),
+    Color,
+    /// A hex color, or one of the given keywords.
+    ColorOr(&'static [&'static str]),
+    StrList,
+    ColorList,
+    InfoKeys,
+    /// A string that takes up at least one column, to be repeated.
+    Pattern,
+}
+
+const SECTIONS: &[&str] = &[
+    "output", "logo", "theme", "display", "colors", "symbols", "modules",
+];
+
+const POSITIONS: &[&str] = &["left", "right", "top", "bottom"];
+const SIZES: &[&str] = &["small", "medium", "large"];
+const LOGO_STYLES: &[&str] = &["ascii", "unicode", "minimal"];
+const ALIGNMENTS: &[&str] = &["left", "center", "right"];
+const BORDER_STYLES: &[&str] = &["single", "double", "rounded", "heavy", "ascii", "none"];
+
+/// The expected kind of `key` inside `section`, or `None` for unknown keys.
+/// Module tables are passed as `modules.*`.
+fn expected_kind(section: &str, key: &str) -> Option<Kind> {
+    let kind = match (section, key) {
+        ("output", "info_keys") => Kind::InfoKeys,
+        ("output", "logo_position") => Kind::OneOf(POSITIONS),
+        ("output", "logo_size") => Kind::OneOf(SIZES),
+        ("output", "logo_style") => Kind::OneOf(LOGO_STYLES),
+        ("output", "logo_color") => Kind::ColorOr(&["auto", "none"]),
+        ("output", key) if key.starts_with("show_") => Kind::Bool,
+
+        ("logo", "enabled") => Kind::Bool,
+        ("logo", "distro" | "file") => Kind::Str,
+        ("logo", "custom_ascii") => Kind::StrList,
+        ("logo", "custom_colors") => Kind::ColorList,
+        ("logo", "padding") => Kind::Integer,
+        ("logo", "alignment") => Kind::OneOf(ALIGNMENTS),
+
+        ("theme", "name") => Kind::Str,
+        ("theme", "background_color") => Kind::ColorOr(&[""]),
+        ("theme", key) if key.ends_with("_color") => Kind::Color,
+
+        ("display", "width" | "height" | "padding" | "margin") => Kind::Integer,
+        ("display", "border" | "compact" | "show_separators") => Kind::Bool,
+        ("display", "border_style") => Kind::OneOf(BORDER_STYLES),
+        ("display", "border_color") => Kind::Color,
+        ("display", "alignment") => Kind::OneOf(ALIGNMENTS),
+        // "dash", "equals", "dots", "none", or any custom pattern
+        ("display", "separator_style") => Kind::Pattern,
+
+        ("colors", _) => Kind::Color,
+        ("symbols", _) => Kind::Str,
+
+        ("modules" | "modules.*", "timeout_ms") => Kind::Integer,
+        ("modules.*", "interval_ms") => Kind::Integer,
+        ("modules.*", "warning" | "critical") => Kind::Number,
+        ("modules.*", "format") => Kind::Str,
+        ("modules.*", key) if key.starts_with("show_") || key.starts_with("hide_") => Kind::Bool,
+        _ => return None,
+    };
+    Some(kind)
+}
+
+/// Checks one configuration file. `info_keys` are the keys some collector provides.
+pub fn check_file(path: &Path, contents: &str, info_keys: &[&str]) -> Vec<Diagnostic> {
+    let mut checker = Checker {
+        file: path.display().to_string(),
+        locator: Locator::new(contents),
+        info_keys,
+        diagnostics: Vec::new(),
+    };
+
+    match contents.parse::<Value>() {
+        Ok(Value::Table(root)) => checker.check_root(&root),
+        Ok(_) => {}
+        Err(e) => {
+            let (line, column) = e.line_col().map(|(l, c)| (l + 1, c + 1)).unwrap_or((1, 1));
+            checker.report_at(line, column, format!("invalid TOML: {}", e));
+        }
+    }
+    checker
+        .diagnostics
+        .sort_by_key(|diagnostic| (diagnostic.line, diagnostic.column));
+    checker.diagnostics
+}
+
+struct Checker<'a> {
+    file: String,
+    locator: Locator,
+    info_keys: &'a [&'a str],
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Checker<'_> {
+    fn check_root(&mut self, root: &Table) {
+        for (key, value) in root {
+            let path = [key.as_str()];
+            if key == "layout" {
+                self.check_value(&path, value, &Kind::Str);
+            } else if SECTIONS.contains(&key.as_str()) {
+                match value.as_table() {
+                    Some(table) => self.check_section(key, table),
+                    None => self.report(&path, format!("`{}` should be a table", key)),
+                }
+            } else {
+                self.report(&path, format!("unknown key `{}`", key));
+            }
+        }
+    }
+
+    fn check_section(&mut self, section: &str, table: &Table) {
+        for (key, value) in table {
+            let path = [section, key.as_str()];
+            if section == "modules" {
+                if let Some(module) = value.as_table() {
+                    self.check_module(key, module);
+                    continue;
+                }
+            }
+            match expected_kind(section, key) {
+                Some(kind) => self.check_value(&path, value, &kind),
+                None => self.report(&path, format!("unknown key `{}.{}`", section, key)),
+            }
+        }
+    }
+
+    fn check_module(&mut self, name: &str, table: &Table) {
+        if !self.info_keys.contains(&name) {
+            self.report(
+                &["modules", name],
+                format!("no collector provides the module `{}`", name),
+            );
+        }
+        for (key, value) in table {
+            let path = ["modules", name, key.as_str()];
+            match expected_kind("modules.*", key) {
+                Some(kind) => self.check_value(&path, value, &kind),
+                None => self.report(
+                    &path,
+                    format!("unknown key `{}` for module `{}`", key, name),
+                ),
+            }
+        }
+    }
+
+    fn check_value(&mut self, path: &[&str], value: &Value, kind: &Kind) {
+        let name = path.join(".");
+        match kind {
+            Kind::Str => {
+                if !value.is_str() {
+                    self.report_type(path, "a string", value);
+                }
+            }
+            Kind::Bool => {
+                if !value.is_bool() {
+                    self.report_type(path, "a boolean", value);
+                }
+            }
+            Kind::Integer => match value.as_integer() {
+                Some(n) if n < 0 => {
+                    self.report(path, format!("`{}` must not be negative", name));
+                }
+                Some(_) => {}
+                None => self.report_type(path, "an integer", value),
+            },
+            Kind::Number => {
+                if value.as_integer().is_none() && value.as_float().is_none() {
+                    self.report_type(path, "a number", value);
+                }
+            }
+            Kind::OneOf(allowed) => match value.as_str() {
+                Some(s) if allowed.contains(&s) => {}
+                Some(s) => self.report(
+                    path,
+                    format!(
+                        "invalid value \"{}\" for `{}` (expected one of: {})",
+                        s,
+                        name,
+                        allowed.join(", ")
+                    ),
+                ),
+                None => self.report_type(path, "a string", value),
+            },
+            Kind::Pattern => match value.as_str() {
+                Some(s) if s.chars().any(char::is_control) => self.report(
+                    path,
+                    format!("`{}` must not contain control characters", name),
+                ),
+                Some(s) if layout::display_width(s) == 0 => {
+                    self.report(path, format!("`{}` must not be empty", name));
+                }
+                Some(_) => {}
+                None => self.report_type(path, "a string", value),
+            },
+            Kind::Color => self.check_color(path, value, &[]),
+            Kind::ColorOr(keywords) => self.check_color(path, value, keywords),
+            Kind::StrList | Kind::ColorList | Kind::InfoKeys => {
+                let items = match value.as_array() {
+                    Some(items) => items,
+                    None => return self.report_type(path, "an array", value),
+                };
+                for item in items {
+                    let Some(s) = item.as_str() else {
+                        self.report_type(path, "an array of strings", value);
+                        return;
+                    };
+                    match kind {
+                        Kind::ColorList if !is_hex_color(s) => {
+                            self.report_item(path, s, malformed_color(s, &name));
+                        }
+                        Kind::InfoKeys if s != GROUP_SEPARATOR && !self.info_keys.contains(&s) => {
+                            self.report_item(
+                                path,
+                                s,
+                                format!("no collector provides info key \"{}\"", s),
+                            );
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+
+    fn check_color(&mut self, path: &[&str], value: &Value, keywords: &[&str]) {
+        match value.as_str() {
+            Some(s) if is_hex_color(s) || keywords.contains(&s) => {}
+            Some(s) => self.report(path, malformed_color(s, &path.join("."))),
+            None => self.report_type(path, "a color string", value),
+        }
+    }
+
+    fn report_type(&mut self, path: &[&str], expected: &str, value: &Value) {
+        let message = format!(
+            "`{}` should be {}, found {}",
+            path.join("."),
+            expected,
+            value.type_str()
+        );
+        self.report(path, message);
+    }
+
+    fn report(&mut self, path: &[&str], message: String) {
+        let (line, column) = self.locator.key(path);
+        self.report_at(line, column, message);
+    }
+
+    /// Reports a problem with one string inside an array.
+    fn report_item(&mut self, path: &[&str], item: &str, message: String) {
+        let (line, column) = self.locator.item(path, item);
+        self.report_at(line, column, message);
+    }
+
+    fn report_at(&mut self, line: usize, column: usize, message: String) {
+        self.diagnostics.push(Diagnostic {
+            file: self.file.clone(),
+            line,
+            column,
+            message,
+        });
+    }
+}
+
+fn is_hex_color(s: &str) -> bool {
+    s.len() == 7 && s.starts_with('#') && s[1..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn malformed_color(s: &str, name: &str) -> String {
+    format!(
+        "malformed hex color \"{}\" for `{}` (expected #RRGGBB)",
+        s, name
+    )
+}
+
+/// Maps key paths back to 1-based line and column numbers in the source.
+/// This only understands the plain `[table]` and `key = value` forms used by
+/// piko configs; anything else is reported at the start of its table.
+struct Locator {
+    lines: Vec<String>,
+    /// (key path, line, column) for every table header and key.
+    keys: Vec<(Vec<String>, usize, usize)>,
+}
+
+impl Locator {
+    fn new(source: &str) -> Self {
+        let mut keys = Vec::new();
+        let mut table: Vec<String> = Vec::new();
+        // Open brackets of a multi-line array, whose items are not keys
+        let mut depth = 0;
+
+        for (index, line) in source.lines().enumerate() {
+            let trimmed = line.trim_start();
+            let column = line.len() - trimmed.len() + 1;
+
+            if depth > 0 {
+                depth = bracket_depth(depth, trimmed);
+                continue;
+            }
+            if trimmed.starts_with("[[") {
+                continue;
+            }
+            if let Some(header) = trimmed.strip_prefix('[') {
+                if let Some(end) = header.find(']') {
+                    table = split_dotted(&header[..end]);
+                    keys.push((table.clone(), index + 1, column));
+                }
+                continue;
+            }
+            if trimmed.starts_with('#') {
+                continue;
+            }
+            if let Some(eq) = trimmed.find('=') {
+                let mut path = table.clone();
+                path.extend(split_dotted(&trimmed[..eq]));
+                keys.push((path, index + 1, column));
+                depth = bracket_depth(0, &trimmed[eq + 1..]);
+            }
+        }
+
+        Locator {
+            lines: source.lines().map(String::from).collect(),
+            keys,
+        }
+    }
+
+    fn find(&self, path: &[&str]) -> Option<(usize, usize)> {
+        self.keys
+            .iter()
+            .find(|(key, _, _)| key.iter().map(String::as_str).eq(path.iter().copied()))
+            .map(|(_, line, column)| (*line, *column))
+    }
+
+    /// The position of `path`, falling back to its closest enclosing table.
+    fn key(&self, path: &[&str]) -> (usize, usize) {
+        (1..=path.len())
+            .rev()
+            .find_map(|len| self.find(&path[..len]))
+            .unwrap_or((1, 1))
+    }
+
+    /// The position of the quoted string `item` in the array at `path`.
+    fn item(&self, path: &[&str], item: &str) -> (usize, usize) {
+        let (line, column) = self.key(path);
+        let needles = [format!("\"{}\"", item), format!("'{}'", item)];
+        for (index, text) in self.lines.iter().enumerate().skip(line - 1) {
+            let start = if index + 1 == line { column - 1 } else { 0 };
+            let found = needles
+                .iter()
+                .filter_map(|needle| text.get(start..)?.find(needle.as_str()))
+                .min();
+            if let Some(offset) = found {
+                return (index + 1, start + offset + 1);
+            }
+            // Stop at the next key or table; the array has ended by then.
+            if index + 1 > line && self.keys.iter().any(|(_, l, _)| *l == index + 1) {
+                break;
+            }
+        }
+        (line, column)
+    }
+}
+
+/// `depth` after the brackets in `text`, skipping strings and comments.
+fn bracket_depth(mut depth: usize, text: &str) -> usize {
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+
+    for c in text.chars() {
+        match (quote, c) {
+            (Some('"'), '\\') if !escaped => {
+                escaped = true;
+                continue;
+            }
+            (Some(q), c) if c == q && !escaped => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '#') => break,
+            (None, '[') => depth += 1,
+            (None, ']') => depth = depth.saturating_sub(1),
+            (None, _) => {}
+        }
+        escaped = false;
+    }
+    depth
+}
+
+/// Splits a dotted TOML key such as `modules."GPU Model"` into its parts.
+fn split_dotted(key: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+
+    for c in key.trim().chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => current.push(c),
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '.') => parts.push(std::mem::take(&mut current)),
+            (None, c) if c.is_whitespace() => {}
+            (None, c) => current.push(c),
+        }
+    }
+    parts.push(current);
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(source: &str) -> Vec<String> {
+        check_file(Path::new("piko.toml"), source, &["OS"])
+            .into_iter()
+            .map(|diagnostic| diagnostic.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn separator_patterns_must_take_up_columns() {
+        assert!(messages("[display]\nseparator_style = \"-~\"\n").is_empty());
+        assert_eq!(
+            messages("[display]\nseparator_style = \"\\t\"\n"),
+            ["piko.toml:2:1: `display.separator_style` must not contain control characters"]
+        );
+        assert_eq!(
+            messages("[display]\nseparator_style = \"\\u0085\"\n"),
+            ["piko.toml:2:1: `display.separator_style` must not contain control characters"]
+        );
+        assert_eq!(
+            messages("[display]\nseparator_style = \"\\u200b\"\n"),
+            ["piko.toml:2:1: `display.separator_style` must not be empty"]
+        );
+    }
+
+    #[test]
+    fn item_is_found_on_its_own_line_of_a_multiline_array() {
+        let source = "[output]\ninfo_keys = [\n    \"OS\",\n    \"Bogus\",\n]\n";
+        let locator = Locator::new(source);
+        assert_eq!(locator.item(&["output", "info_keys"], "Bogus"), (4, 5));
+    }
+
+    #[test]
+    fn item_on_the_key_line_is_found_after_the_key() {
+        let source = "[output]\n  info_keys = ['OS', 'Bogus']\n";
+        let locator = Locator::new(source);
+        assert_eq!(locator.item(&["output", "info_keys"], "Bogus"), (2, 22));
+    }
+
+    #[test]
+    fn equals_sign_inside_array_strings_is_not_a_key() {
+        let source =
+            "[output]\ninfo_keys = [\n    \"a=b\",\n    \"Bogus\",\n]\nlogo_size = \"small\"\n";
+        let locator = Locator::new(source);
+        assert_eq!(locator.find(&["output", "a"]), None);
+        assert_eq!(locator.item(&["output", "info_keys"], "Bogus"), (4, 5));
+        assert_eq!(locator.key(&["output", "logo_size"]), (6, 1));
+    }
+
+    #[test]
+    fn missing_item_falls_back_to_the_key() {
+        let source = "[output]\ninfo_keys = [\"OS\"]\n\n[logo]\ndistro = \"Bogus\"\n";
+        let locator = Locator::new(source);
+        assert_eq!(locator.item(&["output", "info_keys"], "Bogus"), (2, 1));
+    }
+
+    #[test]
+    fn unknown_key_falls_back_to_its_table() {
+        let source = "# comment\n[modules.\"GPU Model\"]\ntimeout_ms = 100\n";
+        let locator = Locator::new(source);
+        assert_eq!(locator.key(&["modules", "GPU Model", "bogus"]), (2, 1));
+        assert_eq!(locator.key(&["modules", "GPU Model", "timeout_ms"]), (3, 1));
+        assert_eq!(locator.key(&["bogus"]), (1, 1));
+    }
+}